@@ -1,50 +1,77 @@
-use std::{env, fs::File, io::{BufWriter, Write}, path::Path};
-
-use crate::{attack::{BISHOP_TABLE_SIZE, ROOK_TABLE_SIZE, bishop_table, king_table, knight_table, pawn_table, rook_table}, magic::{bishop_magic_table, rook_magic_table}, mask::{between, line}};
-
-mod mask;
-mod magic;
-mod attack;
-
-fn write_mask() {
-    let out = env::var_os("OUT_DIR").unwrap();
-    let path = Path::new(&out).join("mask.rs");
-    let file = File::create(path).unwrap();
-    let mut buffer = BufWriter::new(file);
-
-    writeln!(buffer, "static {}: [[u64; 64]; 64] = {:?};", "BETWEENS", between()).unwrap();
-    writeln!(buffer, "static {}: [[u64; 64]; 64] = {:?};", "LINES", line()).unwrap();
-}
-
-fn write_magic() {
-    let out = env::var_os("OUT_DIR").unwrap();
-    let path = Path::new(&out).join("magic.rs");
-    let file = File::create(path).unwrap();
-    let mut buffer = BufWriter::new(file);
-
-    writeln!(buffer, "pub static {}: [Magic; 64] = {:?};", "BISHOP_MAGICS", bishop_magic_table()).unwrap();
-    writeln!(buffer, "pub static {}: [Magic; 64] = {:?};", "ROOK_MAGICS", rook_magic_table()).unwrap();
-}
-
-fn write_attack() {
-    let out = env::var_os("OUT_DIR").unwrap();
-    let path = Path::new(&out).join("attack.rs");
-    let file = File::create(path).unwrap();
-    let mut buffer = BufWriter::new(file);
-
-    writeln!(buffer, "static {}: [[u64; 64]; 2] = {:?};", "PAWN_ATTACKS", pawn_table()).unwrap();
-    writeln!(buffer, "static {}: [u64; 64] = {:?};", "KING_ATTACKS", king_table()).unwrap();
-    writeln!(buffer, "static {}: [u64; 64] = {:?};", "KNIGHT_ATTACKS", knight_table()).unwrap();
-    writeln!(buffer, "static {}: [u64; {}] = {:?};", "BISHOP_ATTACKS", BISHOP_TABLE_SIZE, bishop_table()).unwrap();
-    writeln!(buffer, "static {}: [u64; {}] = {:?};", "ROOK_ATTACKS", ROOK_TABLE_SIZE, rook_table()).unwrap();
-}
-
-fn main() {
-    write_mask();
-    write_magic();
-    write_attack();
-
-    println!("cargo::rerun-if-changed=build/magic.rs");
-    println!("cargo::rerun-if-changed=build/attack.rs");
-    println!("cargo::rerun-if-changed=build/build.rs");
+use std::{env, fs::File, io::{BufWriter, Write}, path::Path};
+
+use crate::{attack::{dense_table, king_table, knight_table, pawn_table, slider_table}, magic::{BISHOP_DELTA, Magic, MagicConfig, ROOK_DELTA, bishop_magic_table, rook_magic_table}, mask::{between, king_zone, line, outpost, passed}};
+
+mod mask;
+mod magic;
+mod attack;
+
+fn write_mask() {
+    let out = env::var_os("OUT_DIR").unwrap();
+    let path = Path::new(&out).join("mask.rs");
+    let file = File::create(path).unwrap();
+    let mut buffer = BufWriter::new(file);
+
+    writeln!(buffer, "static BETWEENS: [[u64; 64]; 64] = {:?};", between()).unwrap();
+    writeln!(buffer, "static LINES: [[u64; 64]; 64] = {:?};", line()).unwrap();
+    writeln!(buffer, "static PASSED: [[u64; 64]; 2] = {:?};", passed()).unwrap();
+    writeln!(buffer, "static OUTPOSTS: [[u64; 64]; 2] = {:?};", outpost()).unwrap();
+    writeln!(buffer, "static KING_ZONES: [[u64; 64]; 2] = {:?};", king_zone()).unwrap();
+}
+
+fn write_magic(bishop_magics: &[Magic], rook_magics: &[Magic]) {
+    let out = env::var_os("OUT_DIR").unwrap();
+    let path = Path::new(&out).join("magic.rs");
+    let file = File::create(path).unwrap();
+    let mut buffer = BufWriter::new(file);
+
+    writeln!(buffer, "pub static BISHOP_MAGICS: [Magic; 64] = {:?};", bishop_magics).unwrap();
+    writeln!(buffer, "pub static ROOK_MAGICS: [Magic; 64] = {:?};", rook_magics).unwrap();
+}
+
+fn write_slider(buffer: &mut BufWriter<File>, name: &str, table: Vec<u64>, dense: bool) {
+    if dense {
+        let (indices, attacks) = dense_table(&table);
+
+        writeln!(buffer, "static {}_INDICES: [u16; {}] = {:?};", name, indices.len(), indices).unwrap();
+        writeln!(buffer, "static {}_ATTACKS: [u64; {}] = {:?};", name, attacks.len(), attacks).unwrap();
+    }
+    else {
+        writeln!(buffer, "static {}_ATTACKS: [u64; {}] = {:?};", name, table.len(), table).unwrap();
+    }
+}
+
+fn write_attack(bishop_magics: &[Magic], rook_magics: &[Magic], config: &MagicConfig) {
+    let out = env::var_os("OUT_DIR").unwrap();
+    let path = Path::new(&out).join("attack.rs");
+    let file = File::create(path).unwrap();
+    let mut buffer = BufWriter::new(file);
+
+    writeln!(buffer, "static PAWN_ATTACKS: [[u64; 64]; 2] = {:?};", pawn_table()).unwrap();
+    writeln!(buffer, "static KING_ATTACKS: [u64; 64] = {:?};", king_table()).unwrap();
+    writeln!(buffer, "static KNIGHT_ATTACKS: [u64; 64] = {:?};", knight_table()).unwrap();
+
+    write_slider(&mut buffer, "BISHOP", slider_table(bishop_magics, BISHOP_DELTA, config.pext), config.dense);
+    write_slider(&mut buffer, "ROOK", slider_table(rook_magics, ROOK_DELTA, config.pext), config.dense);
+}
+
+fn main() {
+    let config = MagicConfig {
+        pext: env::var_os("CARGO_FEATURE_PEXT").is_some(),
+        dense: env::var_os("CARGO_FEATURE_DENSE_MAGIC").is_some(),
+        seed: env::var("TEA_MAGIC_SEED").ok().and_then(|seed| seed.parse().ok()).unwrap_or(0x7ea5eed)
+    };
+
+    let bishop_magics = bishop_magic_table(&config);
+    let rook_magics = rook_magic_table(&config);
+
+    write_mask();
+    write_magic(&bishop_magics, &rook_magics);
+    write_attack(&bishop_magics, &rook_magics, &config);
+
+    println!("cargo::rerun-if-changed=build/mask.rs");
+    println!("cargo::rerun-if-changed=build/magic.rs");
+    println!("cargo::rerun-if-changed=build/attack.rs");
+    println!("cargo::rerun-if-changed=build/build.rs");
+    println!("cargo::rerun-if-env-changed=TEA_MAGIC_SEED");
 }
\ No newline at end of file
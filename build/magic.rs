@@ -1,134 +1,212 @@
-const RANKS: [u64; 8] = [
-    0x00000000000000ff,
-    0x000000000000ff00,
-    0x0000000000ff0000,
-    0x00000000ff000000,
-    0x000000ff00000000,
-    0x0000ff0000000000,
-    0x00ff000000000000,
-    0xff00000000000000
-];
-
-const FILES: [u64; 8] = [
-    0x0101010101010101,
-    0x0202020202020202,
-    0x0404040404040404,
-    0x0808080808080808,
-    0x1010101010101010,
-    0x2020202020202020,
-    0x4040404040404040,
-    0x8080808080808080
-];
-
-const RANK_18: u64 = RANKS[0] | RANKS[7];
-const FILE_AB: u64 = FILES[0] | FILES[7];
-
-pub const BISHOP_DELTA: [(i32, i32); 4] = [(-1, -1), (-1, 1), (1, -1), (1, 1)];
-pub const ROOK_DELTA: [(i32, i32); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
-
-const BISHOP_MAGICS: [u64; 64] = [
-    0xffedf9fd7cfcffff, 0xfc0962854a77f576, 0x5822022042000000, 0x2ca804a100200020,
-    0x0204042200000900, 0x2002121024000002, 0xfc0a66c64a7ef576, 0x7ffdfdfcbd79ffff,
-    0xfc0846a64a34fff6, 0xfc087a874a3cf7f6, 0x1001080204002100, 0x1810080489021800,
-    0x0062040420010a00, 0x5028043004300020, 0xfc0864ae59b4ff76, 0x3c0860af4b35ff76,
-    0x73c01af56cf4cffb, 0x41a01cfad64aaffc, 0x040c0422080a0598, 0x4228020082004050,
-    0x0200800400e00100, 0x020b001230021040, 0x7c0c028f5b34ff76, 0xfc0a028e5ab4df76,
-    0x0020208050a42180, 0x001004804b280200, 0x2048020024040010, 0x0102c04004010200,
-    0x020408204c002010, 0x02411100020080c1, 0x102a008084042100, 0x0941030000a09846,
-    0x0244100800400200, 0x4000901010080696, 0x0000280404180020, 0x0800042008240100,
-    0x0220008400088020, 0x04020182000904c9, 0x0023010400020600, 0x0041040020110302,
-    0xdcefd9b54bfcc09f, 0xf95ffa765afd602b, 0x1401210240484800, 0x0022244208010080,
-    0x1105040104000210, 0x2040088800c40081, 0x43ff9a5cf4ca0c01, 0x4bffcd8e7c587601,
-    0xfc0ff2865334f576, 0xfc0bf6ce5924f576, 0x80000b0401040402, 0x0020004821880a00,
-    0x8200002022440100, 0x0009431801010068, 0xc3ffb7dc36ca8c89, 0xc3ff8a54f4ca2c89,
-    0xfffffcfcfd79edff, 0xfc0863fccb147576, 0x040c000022013020, 0x2000104000420600,
-    0x0400000260142410, 0x0800633408100500, 0xfc087e8e4bb2f736, 0x43ff9e4ef4ca2c89
-];
-
-const ROOK_MAGICS: [u64; 64] = [
-    0xa180022080400230, 0x0040100040022000, 0x0080088020001002, 0x0080080280841000,
-    0x4200042010460008, 0x04800a0003040080, 0x0400110082041008, 0x008000a041000880,
-    0x10138001a080c010, 0x0000804008200480, 0x00010011012000c0, 0x0022004128102200,
-    0x000200081201200c, 0x202a001048460004, 0x0081000100420004, 0x4000800380004500,
-    0x0000208002904001, 0x0090004040026008, 0x0208808010002001, 0x2002020020704940,
-    0x8048010008110005, 0x6820808004002200, 0x0a80040008023011, 0x00b1460000811044,
-    0x4204400080008ea0, 0xb002400180200184, 0x2020200080100380, 0x0010080080100080,
-    0x2204080080800400, 0x0000a40080360080, 0x02040604002810b1, 0x008c218600004104,
-    0x8180004000402000, 0x488c402000401001, 0x4018a00080801004, 0x1230002105001008,
-    0x8904800800800400, 0x0042000c42003810, 0x008408110400b012, 0x0018086182000401,
-    0x2240088020c28000, 0x001001201040c004, 0x0a02008010420020, 0x0010003009010060,
-    0x0004008008008014, 0x0080020004008080, 0x0282020001008080, 0x50000181204a0004,
-    0x48fffe99fecfaa00, 0x48fffe99fecfaa00, 0x497fffadff9c2e00, 0x613fffddffce9200,
-    0xffffffe9ffe7ce00, 0xfffffff5fff3e600, 0x0010301802830400, 0x510ffff5f63c96a0,
-    0xebffffb9ff9fc526, 0x61fffeddfeedaeae, 0x53bfffedffdeb1a2, 0x127fffb9ffdfb5f6,
-    0x411fffddffdbf4d6, 0x0801000804000603, 0x0003ffef27eebe74, 0x7645fffecbfea79e
-];
-
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
-pub struct Magic {
-    pub mask: u64,
-    pub magic: u64,
-    pub shift: u32,
-    pub offset: usize
-}
-
-impl Magic {
-    pub const fn index(self, occupied: u64) -> usize {
-        ((occupied & self.mask).wrapping_mul(self.magic) >> self.shift) as usize + self.offset
-    }
-}
-
-pub fn slider_attacks(square: usize, occupied: u64, delta: [(i32, i32); 4]) -> u64
-{
-    let mut result = 0;
-
-    for i in 0..4 {
-        let mut rank = square as i32 / 8;
-        let mut file = square as i32 % 8;
-
-        loop {
-            rank += delta[i].0;
-            file += delta[i].1;
-
-            if rank < 0 || file < 0 || rank > 7 || file > 7 {
-                break;
-            }
-
-            let bit = 1u64 << (file + rank * 8);
-
-            result |= bit;
-
-            if occupied & bit != 0 {
-                break;
-            }
-        }
-    }
-
-    result
-}
-
-pub fn magic_table(magics: [u64; 64], delta: [(i32, i32); 4]) -> Vec<Magic> {
-    let mut table = vec![Magic { mask: 0, magic: 0, shift: 0, offset: 0 }; 64];
-
-    for square in 0..64 {
-        let edge = (RANK_18 & !RANKS[square / 8]) | (FILE_AB & !FILES[square % 8]);
-
-        table[square].mask = slider_attacks(square, 0, delta) & !edge;
-        table[square].magic = magics[square];
-        table[square].shift = 64 - table[square].mask.count_ones();
-
-        if square < 63 {
-            table[square + 1].offset = table[square].offset + (1 << table[square].mask.count_ones());
-        }
-    }
-
-    table
-}
-
-pub fn bishop_magic_table() -> Vec<Magic> {
-    magic_table(BISHOP_MAGICS, BISHOP_DELTA)
-}
-
-pub fn rook_magic_table() -> Vec<Magic> {
-    magic_table(ROOK_MAGICS, ROOK_DELTA)
+const RANKS: [u64; 8] = [
+    0x00000000000000ff,
+    0x000000000000ff00,
+    0x0000000000ff0000,
+    0x00000000ff000000,
+    0x000000ff00000000,
+    0x0000ff0000000000,
+    0x00ff000000000000,
+    0xff00000000000000
+];
+
+const FILES: [u64; 8] = [
+    0x0101010101010101,
+    0x0202020202020202,
+    0x0404040404040404,
+    0x0808080808080808,
+    0x1010101010101010,
+    0x2020202020202020,
+    0x4040404040404040,
+    0x8080808080808080
+];
+
+const RANK_18: u64 = RANKS[0] | RANKS[7];
+const FILE_AB: u64 = FILES[0] | FILES[7];
+
+pub const BISHOP_DELTA: [(i32, i32); 4] = [(-1, -1), (-1, 1), (1, -1), (1, 1)];
+pub const ROOK_DELTA: [(i32, i32); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Magic {
+    pub mask: u64,
+    pub magic: u64,
+    pub shift: u32,
+    pub offset: usize
+}
+
+impl Magic {
+    pub const fn index(self, occupied: u64) -> usize {
+        ((occupied & self.mask).wrapping_mul(self.magic) >> self.shift) as usize + self.offset
+    }
+
+    pub const fn pext_index(self, occupied: u64) -> usize {
+        pext(occupied, self.mask) as usize + self.offset
+    }
+}
+
+// Software parallel bit extract, the generated tables must match the hardware instruction used at runtime
+pub const fn pext(value: u64, mut mask: u64) -> u64 {
+    let mut result = 0;
+    let mut bit = 1;
+
+    while mask != 0 {
+        if value & mask & mask.wrapping_neg() != 0 {
+            result |= bit;
+        }
+
+        mask &= mask - 1;
+        bit <<= 1;
+    }
+
+    result
+}
+
+pub fn slider_attacks(square: usize, occupied: u64, delta: [(i32, i32); 4]) -> u64
+{
+    let mut result = 0;
+
+    for (delta_rank, delta_file) in delta {
+        let mut rank = square as i32 / 8;
+        let mut file = square as i32 % 8;
+
+        loop {
+            rank += delta_rank;
+            file += delta_file;
+
+            if rank < 0 || file < 0 || rank > 7 || file > 7 {
+                break;
+            }
+
+            let bit = 1u64 << (file + rank * 8);
+
+            result |= bit;
+
+            if occupied & bit != 0 {
+                break;
+            }
+        }
+    }
+
+    result
+}
+
+pub struct MagicConfig {
+    pub pext: bool,
+    pub dense: bool,
+    pub seed: u64
+}
+
+struct Random {
+    seed: u64
+}
+
+impl Random {
+    // Xorshift never leaves zero, so a zero seed would search forever
+    const fn new(seed: u64) -> Self {
+        Self { seed: if seed == 0 { 0x9e3779b97f4a7c15 } else { seed } }
+    }
+
+    fn next(&mut self) -> u64 {
+        self.seed ^= self.seed >> 12;
+        self.seed ^= self.seed << 25;
+        self.seed ^= self.seed >> 27;
+
+        self.seed.wrapping_mul(2685821657736338717)
+    }
+
+    // Magics with few set bits are found much faster
+    fn sparse(&mut self) -> u64 {
+        self.next() & self.next() & self.next()
+    }
+}
+
+fn slider_mask(square: usize, delta: [(i32, i32); 4]) -> u64 {
+    let edge = (RANK_18 & !RANKS[square / 8]) | (FILE_AB & !FILES[square % 8]);
+
+    slider_attacks(square, 0, delta) & !edge
+}
+
+fn subsets(mask: u64) -> Vec<u64> {
+    let mut subsets = Vec::new();
+    let mut occupied = 0u64;
+
+    loop {
+        subsets.push(occupied);
+
+        occupied = occupied.wrapping_sub(mask) & mask;
+
+        if occupied == 0 {
+            break;
+        }
+    }
+
+    subsets
+}
+
+// Tries sparse random candidates until every occupancy maps to a slot without a destructive collision
+fn find_magic(square: usize, mask: u64, delta: [(i32, i32); 4], random: &mut Random) -> u64 {
+    let shift = 64 - mask.count_ones();
+    let occupancies = subsets(mask);
+    let attacks: Vec<u64> = occupancies.iter().map(|&occupied| slider_attacks(square, occupied, delta)).collect();
+
+    let mut used = vec![0u64; occupancies.len()];
+    let mut epoch = vec![0u32; occupancies.len()];
+    let mut attempt = 0;
+
+    loop {
+        let magic = random.sparse();
+
+        attempt += 1;
+
+        if (mask.wrapping_mul(magic) >> 56).count_ones() < 6 {
+            continue;
+        }
+
+        let found = occupancies.iter().zip(attacks.iter()).all(|(&occupied, &attack)| {
+            let index = (occupied.wrapping_mul(magic) >> shift) as usize;
+
+            if epoch[index] != attempt {
+                epoch[index] = attempt;
+                used[index] = attack;
+
+                return true;
+            }
+
+            used[index] == attack
+        });
+
+        if found {
+            return magic;
+        }
+    }
+}
+
+pub fn magic_table(delta: [(i32, i32); 4], config: &MagicConfig) -> Vec<Magic> {
+    let mut random = Random::new(config.seed);
+    let mut table = vec![Magic { mask: 0, magic: 0, shift: 0, offset: 0 }; 64];
+
+    for square in 0..64 {
+        let mask = slider_mask(square, delta);
+
+        table[square].mask = mask;
+
+        if !config.pext {
+            table[square].magic = find_magic(square, mask, delta, &mut random);
+            table[square].shift = 64 - mask.count_ones();
+        }
+
+        if square < 63 {
+            table[square + 1].offset = table[square].offset + (1 << mask.count_ones());
+        }
+    }
+
+    table
+}
+
+pub fn bishop_magic_table(config: &MagicConfig) -> Vec<Magic> {
+    magic_table(BISHOP_DELTA, config)
+}
+
+pub fn rook_magic_table(config: &MagicConfig) -> Vec<Magic> {
+    magic_table(ROOK_DELTA, config)
 }
\ No newline at end of file
@@ -1,132 +1,203 @@
-fn file(square: i32) -> i32 {
-    square % 8
-}
-
-fn rank(square: i32) -> i32 {
-    square / 8
-}
-
-fn square(rank: i32, file: i32) -> i32 {
-    rank * 8 + file
-}
-
-pub fn between() -> Vec<[u64; 64]> {
-    let mut table = vec![[0; 64]; 64];
-
-    for a in 0..64 {
-        for b in 0..64 {
-            if a == b {
-                continue;
-            }
-
-            let mut mask: u64 = 0;
-
-            if rank(a) == rank(b) {
-                let rank = rank(a);
-                let mut i = file(a).min(file(b)) + 1;
-
-                while i < file(a).max(file(b)) {
-                    mask |= 1 << square(rank, i);
-                    i += 1;
-                }
-            }
-
-            if file(a) == file(b) {
-                let file = file(a);
-                let mut i = rank(a).min(rank(b)) + 1;
-
-                while i < rank(a).max(rank(b)) {
-                    mask |= 1 << square(i, file);
-                    i += 1;
-                }
-            }
-
-            if file(a).abs_diff(file(b)) == rank(a).abs_diff(rank(b)) {
-                let mut r = rank(a);
-                let mut f = file(a);
-
-                let delta_r = if rank(a) < rank(b) { 1 } else { -1 };
-                let delta_f = if file(a) < file(b) { 1 } else { -1 };
-
-                loop {
-                    r += delta_r;
-                    f += delta_f;
-
-                    if r == rank(b) || f == file(b) {
-                        break;
-                    }
-
-                    mask |= 1 << square(r, f);
-                }
-            }
-
-            table[a as usize][b as usize] = mask;
-            table[b as usize][a as usize] = mask;
-        }
-    }
-
-    table
-}
-
-pub fn line() -> Vec<[u64; 64]> {
-    let mut table = vec![[0; 64]; 64];
-
-    for a in 0..64 {
-        for b in 0..64 {
-            if a == b {
-                continue;
-            }
-
-            let mut mask: u64 = 0;
-
-            if rank(a) == rank(b) {
-                mask = 0xff << (rank(a) * 8);
-            }
-
-            if file(a) == file(b) {
-                mask = 0x0101010101010101 << file(a);
-            }
-
-            if file(a).abs_diff(file(b)) == rank(a).abs_diff(rank(b)) {
-                mask |= 1 << a;
-                mask |= 1 << b;
-
-                let mut r = rank(a);
-                let mut f = file(a);
-
-                let delta_r = if rank(a) < rank(b) { 1 } else { -1 };
-                let delta_f = if file(a) < file(b) { 1 } else { -1 };
-
-                loop {
-                    r += delta_r;
-                    f += delta_f;
-
-                    if r < 0 || f < 0 || r > 7 || f > 7 {
-                        break;
-                    }
-
-                    mask |= 1 << square(r, f);
-                }
-
-                r = rank(a);
-                f = file(a);
-
-                loop {
-                    r -= delta_r;
-                    f -= delta_f;
-
-                    if r < 0 || f < 0 || r > 7 || f > 7 {
-                        break;
-                    }
-
-                    mask |= 1 << square(r, f);
-                }
-            }
-
-            table[a as usize][b as usize] = mask;
-            table[b as usize][a as usize] = mask;
-        }
-    }
-
-    table
+fn file(square: i32) -> i32 {
+    square % 8
+}
+
+fn rank(square: i32) -> i32 {
+    square / 8
+}
+
+fn square(rank: i32, file: i32) -> i32 {
+    rank * 8 + file
+}
+
+pub fn between() -> Vec<[u64; 64]> {
+    let mut table = vec![[0; 64]; 64];
+
+    for a in 0..64 {
+        for b in 0..64 {
+            if a == b {
+                continue;
+            }
+
+            let mut mask: u64 = 0;
+
+            if rank(a) == rank(b) {
+                let rank = rank(a);
+                let mut i = file(a).min(file(b)) + 1;
+
+                while i < file(a).max(file(b)) {
+                    mask |= 1 << square(rank, i);
+                    i += 1;
+                }
+            }
+
+            if file(a) == file(b) {
+                let file = file(a);
+                let mut i = rank(a).min(rank(b)) + 1;
+
+                while i < rank(a).max(rank(b)) {
+                    mask |= 1 << square(i, file);
+                    i += 1;
+                }
+            }
+
+            if file(a).abs_diff(file(b)) == rank(a).abs_diff(rank(b)) {
+                let mut r = rank(a);
+                let mut f = file(a);
+
+                let delta_r = if rank(a) < rank(b) { 1 } else { -1 };
+                let delta_f = if file(a) < file(b) { 1 } else { -1 };
+
+                loop {
+                    r += delta_r;
+                    f += delta_f;
+
+                    if r == rank(b) || f == file(b) {
+                        break;
+                    }
+
+                    mask |= 1 << square(r, f);
+                }
+            }
+
+            table[a as usize][b as usize] = mask;
+            table[b as usize][a as usize] = mask;
+        }
+    }
+
+    table
+}
+
+pub fn line() -> Vec<[u64; 64]> {
+    let mut table = vec![[0; 64]; 64];
+
+    for a in 0..64 {
+        for b in 0..64 {
+            if a == b {
+                continue;
+            }
+
+            let mut mask: u64 = 0;
+
+            if rank(a) == rank(b) {
+                mask = 0xff << (rank(a) * 8);
+            }
+
+            if file(a) == file(b) {
+                mask = 0x0101010101010101 << file(a);
+            }
+
+            if file(a).abs_diff(file(b)) == rank(a).abs_diff(rank(b)) {
+                mask |= 1 << a;
+                mask |= 1 << b;
+
+                let mut r = rank(a);
+                let mut f = file(a);
+
+                let delta_r = if rank(a) < rank(b) { 1 } else { -1 };
+                let delta_f = if file(a) < file(b) { 1 } else { -1 };
+
+                loop {
+                    r += delta_r;
+                    f += delta_f;
+
+                    if r < 0 || f < 0 || r > 7 || f > 7 {
+                        break;
+                    }
+
+                    mask |= 1 << square(r, f);
+                }
+
+                r = rank(a);
+                f = file(a);
+
+                loop {
+                    r -= delta_r;
+                    f -= delta_f;
+
+                    if r < 0 || f < 0 || r > 7 || f > 7 {
+                        break;
+                    }
+
+                    mask |= 1 << square(r, f);
+                }
+            }
+
+            table[a as usize][b as usize] = mask;
+            table[b as usize][a as usize] = mask;
+        }
+    }
+
+    table
+}
+
+// Squares in front of a pawn on its own and adjacent files, no enemy pawn may stand there for it to be passed
+pub fn passed() -> [[u64; 64]; 2] {
+    let mut table = [[0; 64]; 2];
+
+    for a in 0..64 {
+        for b in 0..64 {
+            if file(a).abs_diff(file(b)) > 1 {
+                continue;
+            }
+
+            if rank(b) > rank(a) {
+                table[0][a as usize] |= 1 << b;
+            }
+
+            if rank(b) < rank(a) {
+                table[1][a as usize] |= 1 << b;
+            }
+        }
+    }
+
+    table
+}
+
+// Squares in front of a square on the adjacent files, enemy pawns there can still chase away a piece on it
+pub fn outpost() -> [[u64; 64]; 2] {
+    let mut table = [[0; 64]; 2];
+
+    for a in 0..64 {
+        for b in 0..64 {
+            if file(a).abs_diff(file(b)) != 1 {
+                continue;
+            }
+
+            if rank(b) > rank(a) {
+                table[0][a as usize] |= 1 << b;
+            }
+
+            if rank(b) < rank(a) {
+                table[1][a as usize] |= 1 << b;
+            }
+        }
+    }
+
+    table
+}
+
+// The king square, its neighbours and the three squares two ranks in front of it
+pub fn king_zone() -> [[u64; 64]; 2] {
+    let mut table = [[0; 64]; 2];
+
+    for a in 0..64 {
+        for b in 0..64 {
+            if file(a).abs_diff(file(b)) > 1 {
+                continue;
+            }
+
+            let distance = rank(b) - rank(a);
+
+            if (-1..=2).contains(&distance) {
+                table[0][a as usize] |= 1 << b;
+            }
+
+            if (-2..=1).contains(&distance) {
+                table[1][a as usize] |= 1 << b;
+            }
+        }
+    }
+
+    table
 }
\ No newline at end of file
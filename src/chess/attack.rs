@@ -1,44 +1,106 @@
-include!(concat!(env!("OUT_DIR"), "/attack.rs"));
-
-use crate::chess::{bitboard::Bitboard, color::Color, direction::Direction, square::Square, magic::{BISHOP_MAGICS, ROOK_MAGICS}};
-
-#[inline(always)]
-pub const fn pawn_east_attacks(square: Square, color: Color) -> Bitboard {
-    match color {
-        Color::White => Bitboard::from_square(square).shift(Direction::NorthEast),
-        Color::Black => Bitboard::from_square(square).shift(Direction::SouthWest)
-    }
-}
-
-#[inline(always)]
-pub const fn pawn_west_attacks(square: Square, color: Color) -> Bitboard {
-    match color {
-        Color::White => Bitboard::from_square(square).shift(Direction::NorthWest),
-        Color::Black => Bitboard::from_square(square).shift(Direction::SouthEast)
-    }
-}
-
-#[inline(always)]
-pub fn pawn_attacks(square: Square, color: Color) -> Bitboard {
-    Bitboard::from_raw(PAWN_ATTACKS[color.index()][square.index()])
-}
-
-#[inline(always)]
-pub fn king_attacks(square: Square) -> Bitboard {
-    Bitboard::from_raw(KING_ATTACKS[square.index()])
-}
-
-#[inline(always)]
-pub fn knight_attacks(square: Square) -> Bitboard {
-    Bitboard::from_raw(KNIGHT_ATTACKS[square.index()])
-}
-
-#[inline(always)]
-pub fn bishop_attacks(square: Square, occupied: Bitboard) -> Bitboard {
-    Bitboard::from_raw(BISHOP_ATTACKS[BISHOP_MAGICS[square.index()].index(occupied)])
-}
-
-#[inline(always)]
-pub fn rook_attacks(square: Square, occupied: Bitboard) -> Bitboard {
-    Bitboard::from_raw(ROOK_ATTACKS[ROOK_MAGICS[square.index()].index(occupied)])
+include!(concat!(env!("OUT_DIR"), "/attack.rs"));
+
+use crate::chess::{bitboard::Bitboard, color::Color, direction::Direction, square::Square, magic::{BISHOP_MAGICS, ROOK_MAGICS}};
+
+#[inline(always)]
+pub const fn pawn_east_attacks(square: Square, color: Color) -> Bitboard {
+    match color {
+        Color::White => Bitboard::from_square(square).shift(Direction::NorthEast),
+        Color::Black => Bitboard::from_square(square).shift(Direction::SouthWest)
+    }
+}
+
+#[inline(always)]
+pub const fn pawn_west_attacks(square: Square, color: Color) -> Bitboard {
+    match color {
+        Color::White => Bitboard::from_square(square).shift(Direction::NorthWest),
+        Color::Black => Bitboard::from_square(square).shift(Direction::SouthEast)
+    }
+}
+
+#[inline(always)]
+pub fn pawn_attacks(square: Square, color: Color) -> Bitboard {
+    Bitboard::from_raw(PAWN_ATTACKS[color.index()][square.index()])
+}
+
+#[inline(always)]
+pub fn king_attacks(square: Square) -> Bitboard {
+    Bitboard::from_raw(KING_ATTACKS[square.index()])
+}
+
+#[inline(always)]
+pub fn knight_attacks(square: Square) -> Bitboard {
+    Bitboard::from_raw(KNIGHT_ATTACKS[square.index()])
+}
+
+#[cfg(not(feature = "dense-magic"))]
+#[inline(always)]
+pub fn bishop_attacks(square: Square, occupied: Bitboard) -> Bitboard {
+    Bitboard::from_raw(BISHOP_ATTACKS[BISHOP_MAGICS[square.index()].index(occupied)])
+}
+
+#[cfg(not(feature = "dense-magic"))]
+#[inline(always)]
+pub fn rook_attacks(square: Square, occupied: Bitboard) -> Bitboard {
+    Bitboard::from_raw(ROOK_ATTACKS[ROOK_MAGICS[square.index()].index(occupied)])
+}
+
+#[cfg(feature = "dense-magic")]
+#[inline(always)]
+pub fn bishop_attacks(square: Square, occupied: Bitboard) -> Bitboard {
+    Bitboard::from_raw(BISHOP_ATTACKS[BISHOP_INDICES[BISHOP_MAGICS[square.index()].index(occupied)] as usize])
+}
+
+#[cfg(feature = "dense-magic")]
+#[inline(always)]
+pub fn rook_attacks(square: Square, occupied: Bitboard) -> Bitboard {
+    Bitboard::from_raw(ROOK_ATTACKS[ROOK_INDICES[ROOK_MAGICS[square.index()].index(occupied)] as usize])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chess::random::Random;
+
+    fn slider_attacks(square: Square, occupied: Bitboard, deltas: [(i8, i8); 4]) -> Bitboard {
+        let mut attacks = Bitboard::new();
+
+        for (delta_rank, delta_file) in deltas {
+            let mut rank = square.rank().value() as i8;
+            let mut file = square.file().value() as i8;
+
+            loop {
+                rank += delta_rank;
+                file += delta_file;
+
+                if !(0..8).contains(&rank) || !(0..8).contains(&file) {
+                    break;
+                }
+
+                let target = Square::from_raw(rank as u8 * 8 + file as u8);
+
+                attacks.set(target);
+
+                if occupied.is_set(target) {
+                    break;
+                }
+            }
+        }
+
+        attacks
+    }
+
+    #[test]
+    fn sliders() {
+        let mut random = Random::new(29);
+
+        for _ in 0..10000 {
+            let occupied = Bitboard::from_raw(random.next_u64() & random.next_u64());
+
+            for square in Square::all() {
+                assert_eq!(bishop_attacks(square, occupied), slider_attacks(square, occupied, [(1, 1), (1, -1), (-1, 1), (-1, -1)]));
+                assert_eq!(rook_attacks(square, occupied), slider_attacks(square, occupied, [(1, 0), (-1, 0), (0, 1), (0, -1)]));
+            }
+        }
+    }
 }
\ No newline at end of file
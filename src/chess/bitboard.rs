@@ -1,189 +1,320 @@
-include!(concat!(env!("OUT_DIR"), "/mask.rs"));
-
-use crate::chess::{direction::Direction, file::File, rank::Rank, square::Square};
-
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
-#[repr(transparent)]
-pub struct Bitboard {
-    data: u64
-}
-
-impl Bitboard {
-    #[inline(always)]
-    pub const fn new() -> Self {
-        Self { data: 0 }
-    }
-
-    #[inline(always)]
-    pub const fn from_raw(value: u64) -> Self {
-        Self { data: value }
-    }
-
-    #[inline(always)]
-    pub const fn from_square(square: Square) -> Self {
-        Self { data: 1u64 << square.index() }
-    }
-
-    #[inline(always)]
-    pub const fn from_rank(rank: Rank) -> Self {
-        Self { data: 0xffu64 << (rank.index() * 8) }
-    }
-
-    #[inline(always)]
-    pub const fn from_file(file: File) -> Self {
-        Self { data: 0x0101010101010101u64 << file.index() }
-    }
-
-    #[inline(always)]
-    pub const fn from_between(a: Square, b: Square) -> Self {
-        Self { data: BETWEENS[a.index()][b.index()] }
-    }
-
-    #[inline(always)]
-    pub const fn from_line(a: Square, b: Square) -> Self {
-        Self { data: LINES[a.index()][b.index()] }
-    }
-
-    #[inline(always)]
-    pub const fn value(self) -> u64 {
-        self.data
-    }
-
-    #[inline(always)]
-    pub const fn lsb(self) -> Square {
-        Square::from_raw(self.data.trailing_zeros() as u8)
-    }
-
-    #[inline(always)]
-    pub const fn count(self) -> usize {
-        self.data.count_ones() as usize
-    }
-
-    #[inline(always)]
-    pub const fn shift(self, direction: Direction) -> Self {
-        match direction {
-            Direction::North => Self { data: self.data << 8 },
-            Direction::South => Self { data: self.data >> 8 },
-            Direction::East => Self { data: (self.data & !Self::from_file(File::H).data) << 1 },
-            Direction::West => Self { data: (self.data & !Self::from_file(File::A).data) >> 1 },
-            Direction::NorthEast => Self { data: (self.data & !Self::from_file(File::H).data) << 9 },
-            Direction::NorthWest => Self { data: (self.data & !Self::from_file(File::A).data) << 7 },
-            Direction::SouthEast => Self { data: (self.data & !Self::from_file(File::H).data) >> 7 },
-            Direction::SouthWest => Self { data: (self.data & !Self::from_file(File::A).data) >> 9 } 
-        }
-    }
-
-    #[inline(always)]
-    pub fn set(&mut self, square: Square) {
-        self.data |= 1u64 << square.index();
-    }
-
-    #[inline(always)]
-    pub fn clear(&mut self, square: Square) {
-        self.data &= !(1u64 << square.index());
-    }
-
-    #[inline(always)]
-    pub const fn is_empty(self) -> bool {
-        self.data == 0
-    }
-
-    #[inline(always)]
-    pub const fn is_some(self) -> bool {
-        self.data != 0
-    }
-
-    #[inline(always)]
-    pub const fn is_only(self) -> bool {
-        self.count() == 1
-    }
-
-    #[inline(always)]
-    pub const fn is_many(self) -> bool {
-        self.data & self.data.wrapping_sub(1) != 0
-    }
-
-    #[inline(always)]
-    pub const fn is_set(self, square: Square) -> bool {
-        self.data & (1u64 << square.index()) != 0
-    }
-}
-
-impl Iterator for Bitboard {
-    type Item = Square;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.is_empty() {
-            None
-        }
-        else {
-            let lsb = self.lsb();
-            self.data &= self.data - 1;
-            Some(lsb)
-        }
-    }
-}
-
-impl std::ops::BitAnd for Bitboard {
-    type Output = Self;
-
-    fn bitand(self, rhs: Self) -> Self::Output {
-        Self { data: self.data & rhs.data }
-    }
-}
-
-impl std::ops::BitOr for Bitboard {
-    type Output = Self;
-
-    fn bitor(self, rhs: Self) -> Self::Output {
-        Self { data: self.data | rhs.data }
-    }
-}
-
-impl std::ops::BitXor for Bitboard {
-    type Output = Self;
-
-    fn bitxor(self, rhs: Self) -> Self::Output {
-        Self { data: self.data ^ rhs.data }
-    }
-}
-
-impl std::ops::BitOrAssign for Bitboard {
-    fn bitor_assign(&mut self, rhs: Self) {
-        self.data |= rhs.data;
-    }
-}
-
-impl std::ops::BitAndAssign for Bitboard {
-    fn bitand_assign(&mut self, rhs: Self) {
-        self.data &= rhs.data;
-    }
-}
-
-impl std::ops::BitXorAssign for Bitboard {
-    fn bitxor_assign(&mut self, rhs: Self) {
-        self.data ^= rhs.data;
-    }
-}
-
-impl std::ops::Not for Bitboard {
-    type Output = Self;
-
-    fn not(self) -> Self::Output {
-        Self { data: !self.data }
-    }
-}
-
-impl std::fmt::Display for Bitboard {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for rank in Rank::all().rev() {
-            for file in File::all() {
-                write!(f, "{} ", if self.is_set(Square::new(rank, file)) { 'X' } else { '.' })?;
-            }
-
-            write!(f, "\n")?;
-        }
-
-        Ok(())
-    }
+include!(concat!(env!("OUT_DIR"), "/mask.rs"));
+
+use crate::chess::{color::Color, direction::Direction, file::File, rank::Rank, square::Square};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[repr(transparent)]
+pub struct Bitboard {
+    data: u64
+}
+
+impl Bitboard {
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self { data: 0 }
+    }
+
+    #[inline(always)]
+    pub const fn from_raw(value: u64) -> Self {
+        Self { data: value }
+    }
+
+    #[inline(always)]
+    pub const fn from_square(square: Square) -> Self {
+        Self { data: 1u64 << square.index() }
+    }
+
+    #[inline(always)]
+    pub const fn from_rank(rank: Rank) -> Self {
+        Self { data: 0xffu64 << (rank.index() * 8) }
+    }
+
+    #[inline(always)]
+    pub const fn from_file(file: File) -> Self {
+        Self { data: 0x0101010101010101u64 << file.index() }
+    }
+
+    #[inline(always)]
+    pub const fn from_between(a: Square, b: Square) -> Self {
+        Self { data: BETWEENS[a.index()][b.index()] }
+    }
+
+    #[inline(always)]
+    pub const fn from_line(a: Square, b: Square) -> Self {
+        Self { data: LINES[a.index()][b.index()] }
+    }
+
+    #[inline(always)]
+    pub const fn from_adjacent_files(file: File) -> Self {
+        let files = Self::from_file(file);
+
+        Self { data: files.shift(Direction::East).data | files.shift(Direction::West).data }
+    }
+
+    #[inline(always)]
+    pub const fn from_passed(square: Square, color: Color) -> Self {
+        Self { data: PASSED[color.index()][square.index()] }
+    }
+
+    #[inline(always)]
+    pub const fn from_outpost(square: Square, color: Color) -> Self {
+        Self { data: OUTPOSTS[color.index()][square.index()] }
+    }
+
+    #[inline(always)]
+    pub const fn from_king_zone(square: Square, color: Color) -> Self {
+        Self { data: KING_ZONES[color.index()][square.index()] }
+    }
+
+    #[inline(always)]
+    pub const fn value(self) -> u64 {
+        self.data
+    }
+
+    #[inline(always)]
+    pub const fn lsb(self) -> Square {
+        Square::from_raw(self.data.trailing_zeros() as u8)
+    }
+
+    #[inline(always)]
+    pub const fn count(self) -> usize {
+        self.data.count_ones() as usize
+    }
+
+    #[inline(always)]
+    pub const fn shift(self, direction: Direction) -> Self {
+        match direction {
+            Direction::North => Self { data: self.data << 8 },
+            Direction::South => Self { data: self.data >> 8 },
+            Direction::East => Self { data: (self.data & !Self::from_file(File::H).data) << 1 },
+            Direction::West => Self { data: (self.data & !Self::from_file(File::A).data) >> 1 },
+            Direction::NorthEast => Self { data: (self.data & !Self::from_file(File::H).data) << 9 },
+            Direction::NorthWest => Self { data: (self.data & !Self::from_file(File::A).data) << 7 },
+            Direction::SouthEast => Self { data: (self.data & !Self::from_file(File::H).data) >> 7 },
+            Direction::SouthWest => Self { data: (self.data & !Self::from_file(File::A).data) >> 9 } 
+        }
+    }
+
+    #[inline(always)]
+    pub const fn north_fill(self) -> Self {
+        let mut data = self.data;
+
+        data |= data << 8;
+        data |= data << 16;
+        data |= data << 32;
+
+        Self { data }
+    }
+
+    #[inline(always)]
+    pub const fn south_fill(self) -> Self {
+        let mut data = self.data;
+
+        data |= data >> 8;
+        data |= data >> 16;
+        data |= data >> 32;
+
+        Self { data }
+    }
+
+    #[inline(always)]
+    pub const fn file_fill(self) -> Self {
+        Self { data: self.north_fill().data | self.south_fill().data }
+    }
+
+    // Squares in front of every bit, not including the bits themselves
+    #[inline(always)]
+    pub const fn front_span(self, color: Color) -> Self {
+        match color {
+            Color::White => self.shift(Direction::North).north_fill(),
+            Color::Black => self.shift(Direction::South).south_fill()
+        }
+    }
+
+    // Squares that pawns on these bits can ever attack while advancing
+    #[inline(always)]
+    pub const fn attack_span(self, color: Color) -> Self {
+        let span = self.front_span(color);
+
+        Self { data: span.shift(Direction::East).data | span.shift(Direction::West).data }
+    }
+
+    #[inline(always)]
+    pub fn set(&mut self, square: Square) {
+        self.data |= 1u64 << square.index();
+    }
+
+    #[inline(always)]
+    pub fn clear(&mut self, square: Square) {
+        self.data &= !(1u64 << square.index());
+    }
+
+    #[inline(always)]
+    pub const fn is_empty(self) -> bool {
+        self.data == 0
+    }
+
+    #[inline(always)]
+    pub const fn is_some(self) -> bool {
+        self.data != 0
+    }
+
+    #[inline(always)]
+    pub const fn is_only(self) -> bool {
+        self.count() == 1
+    }
+
+    #[inline(always)]
+    pub const fn is_many(self) -> bool {
+        self.data & self.data.wrapping_sub(1) != 0
+    }
+
+    #[inline(always)]
+    pub const fn is_set(self, square: Square) -> bool {
+        self.data & (1u64 << square.index()) != 0
+    }
+}
+
+impl Iterator for Bitboard {
+    type Item = Square;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.is_empty() {
+            None
+        }
+        else {
+            let lsb = self.lsb();
+            self.data &= self.data - 1;
+            Some(lsb)
+        }
+    }
+}
+
+impl std::ops::BitAnd for Bitboard {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        Self { data: self.data & rhs.data }
+    }
+}
+
+impl std::ops::BitOr for Bitboard {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self { data: self.data | rhs.data }
+    }
+}
+
+impl std::ops::BitXor for Bitboard {
+    type Output = Self;
+
+    fn bitxor(self, rhs: Self) -> Self::Output {
+        Self { data: self.data ^ rhs.data }
+    }
+}
+
+impl std::ops::BitOrAssign for Bitboard {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.data |= rhs.data;
+    }
+}
+
+impl std::ops::BitAndAssign for Bitboard {
+    fn bitand_assign(&mut self, rhs: Self) {
+        self.data &= rhs.data;
+    }
+}
+
+impl std::ops::BitXorAssign for Bitboard {
+    fn bitxor_assign(&mut self, rhs: Self) {
+        self.data ^= rhs.data;
+    }
+}
+
+impl std::ops::Not for Bitboard {
+    type Output = Self;
+
+    fn not(self) -> Self::Output {
+        Self { data: !self.data }
+    }
+}
+
+impl std::fmt::Display for Bitboard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for rank in Rank::all().rev() {
+            for file in File::all() {
+                write!(f, "{} ", if self.is_set(Square::new(rank, file)) { 'X' } else { '.' })?;
+            }
+
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chess::random::Random;
+
+    fn is_ahead(from: Square, to: Square, color: Color) -> bool {
+        match color {
+            Color::White => to.rank() > from.rank(),
+            Color::Black => to.rank() < from.rank()
+        }
+    }
+
+    fn brute(bitboard: Bitboard, filter: impl Fn(Square, Square) -> bool) -> Bitboard {
+        let mut result = Bitboard::new();
+
+        for from in bitboard {
+            for to in Square::all().filter(|&to| filter(from, to)) {
+                result.set(to);
+            }
+        }
+
+        result
+    }
+
+    #[test]
+    fn fills_and_spans() {
+        let mut random = Random::new(31);
+
+        for _ in 0..1000 {
+            let bitboard = Bitboard::from_raw(random.next_u64() & random.next_u64());
+
+            assert_eq!(bitboard.north_fill(), brute(bitboard, |from, to| from.file() == to.file() && to.rank() >= from.rank()));
+            assert_eq!(bitboard.south_fill(), brute(bitboard, |from, to| from.file() == to.file() && to.rank() <= from.rank()));
+            assert_eq!(bitboard.file_fill(), brute(bitboard, |from, to| from.file() == to.file()));
+
+            for color in [Color::White, Color::Black] {
+                assert_eq!(bitboard.front_span(color), brute(bitboard, |from, to| from.file() == to.file() && is_ahead(from, to, color)));
+                assert_eq!(bitboard.attack_span(color), brute(bitboard, |from, to| from.file().distance(to.file()) == 1 && is_ahead(from, to, color)));
+            }
+        }
+    }
+
+    #[test]
+    fn masks() {
+        for file in File::all() {
+            assert_eq!(Bitboard::from_adjacent_files(file), brute(Bitboard::from_file(file), |from, to| from.file().distance(to.file()) == 1));
+        }
+
+        for square in Square::all() {
+            let bitboard = Bitboard::from_square(square);
+
+            for color in [Color::White, Color::Black] {
+                let forward = |from: Square, to: Square| match color {
+                    Color::White => to.rank().value() as i8 - from.rank().value() as i8,
+                    Color::Black => from.rank().value() as i8 - to.rank().value() as i8
+                };
+
+                assert_eq!(Bitboard::from_passed(square, color), brute(bitboard, |from, to| from.file().distance(to.file()) <= 1 && is_ahead(from, to, color)));
+                assert_eq!(Bitboard::from_outpost(square, color), brute(bitboard, |from, to| from.file().distance(to.file()) == 1 && is_ahead(from, to, color)));
+                assert_eq!(Bitboard::from_king_zone(square, color), brute(bitboard, |from, to| from.file().distance(to.file()) <= 1 && (-1..=2).contains(&forward(from, to))));
+            }
+        }
+    }
 }
\ No newline at end of file
@@ -1,573 +1,923 @@
-use crate::chess::{attack::{bishop_attacks, king_attacks, knight_attacks, pawn_attacks, rook_attacks}, bitboard::Bitboard, castle::{Castle, CastleKind}, color::Color, direction::Direction, file::File, moves::Move, piece::{Piece, PieceKind}, rank::Rank, square::Square, zobrist::ZOBRIST};
-
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub enum FenParseError {
-    MissingBoard,
-    MissingColor,
-    MissingCastle,
-    InvalidBoard,
-    InvalidColor,
-    InvalidCastle
-}
-
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
-pub struct Key {
-    pub main: u64
-}
-
-#[derive(Debug, Clone)]
-pub struct State {
-    pieces: [Bitboard; PieceKind::COUNT],
-    colors: [Bitboard; Color::COUNT],
-    mailbox: [Option<Piece>; Square::COUNT],
-    castles: Castle,
-    enpassant: Option<Square>,
-    halfmove: u8,
-    checkers: Bitboard,
-    blockers: [Bitboard; Color::COUNT],
-    keys: Key
-}
-
-#[derive(Debug, Clone)]
-pub struct Board {
-    pub state: State,
-    pub color: Color,
-    pub stack: Vec<State>
-}
-
-impl State {
-    pub const fn new() -> Self {
-        Self {
-            pieces: [Bitboard::new(); PieceKind::COUNT],
-            colors: [Bitboard::new(); Color::COUNT],
-            mailbox: [None; Square::COUNT],
-            castles: Castle::new(),
-            enpassant: None,
-            halfmove: 0,
-            checkers: Bitboard::new(),
-            blockers: [Bitboard::new(); Color::COUNT],
-            keys: Key { main: 0 }
-        }
-    }
-
-    #[inline(always)]
-    pub fn pieces(&self, kind: PieceKind) -> Bitboard {
-        self.pieces[kind]
-    }
-
-    #[inline(always)]
-    pub fn colors(&self, color: Color) -> Bitboard {
-        self.colors[color]
-    }
-
-    #[inline(always)]
-    pub fn at(&self, square: Square) -> Option<Piece> {
-        self.mailbox[square]
-    }
-
-    #[inline(always)]
-    pub fn occupied(&self) -> Bitboard {
-        self.colors[Color::White] | self.colors[Color::Black]
-    }
-
-    #[inline(always)]
-    pub const fn castles(&self) -> Castle {
-        self.castles
-    }
-
-    #[inline(always)]
-    pub const fn enpassant(&self) -> Option<Square> {
-        self.enpassant
-    }
-
-    #[inline(always)]
-    pub const fn halfmove(&self) -> u8 {
-        self.halfmove
-    }
-
-    #[inline(always)]
-    pub const fn checkers(&self) -> Bitboard {
-        self.checkers
-    }
-
-    #[inline(always)]
-    pub fn blockers(&self, color: Color) -> Bitboard {
-        self.blockers[color]
-    }
-
-    #[inline(always)]
-    pub const fn key(&self) -> Key {
-        self.keys
-    }
-
-    #[inline(always)]
-    pub fn king_square(&self, color: Color) -> Square {
-        (self.pieces(PieceKind::King) & self.colors(color)).lsb()
-    }
-
-    pub fn attackers(&self, square: Square, occupied: Bitboard) -> Bitboard {
-        let mut attackers = Bitboard::new();
-
-        attackers |= pawn_attacks(square, Color::White) & self.colors(Color::Black) & self.pieces(PieceKind::Pawn);
-        attackers |= pawn_attacks(square, Color::Black) & self.colors(Color::White) & self.pieces(PieceKind::Pawn);
-        attackers |= king_attacks(square) & self.pieces(PieceKind::King);
-        attackers |= knight_attacks(square) & self.pieces(PieceKind::Knight);
-        attackers |= bishop_attacks(square, occupied) & (self.pieces(PieceKind::Bishop) | self.pieces(PieceKind::Queen));
-        attackers |= rook_attacks(square, occupied) & (self.pieces(PieceKind::Rook) | self.pieces(PieceKind::Queen));
-
-        attackers
-    }
-
-    pub fn place(&mut self, square: Square, piece: Piece) {
-        self.pieces[piece.kind()].set(square);
-        self.colors[piece.color()].set(square);
-        self.mailbox[square] = Some(piece);
-        self.keys.main ^= ZOBRIST.piece[piece][square];
-    }
-
-    pub fn remove(&mut self, square: Square) {
-        if let Some(piece) = self.mailbox[square] {
-            self.pieces[piece.kind()].clear(square);
-            self.colors[piece.color()].clear(square);
-            self.mailbox[square] = None;
-            self.keys.main ^= ZOBRIST.piece[piece][square];
-        }
-    }
-
-    pub fn is_attacked(&self, square: Square, color: Color, occupied: Bitboard) -> bool {
-        (pawn_attacks(square, color) & self.pieces(PieceKind::Pawn) & self.colors(!color)).is_some() ||
-        (king_attacks(square) & self.pieces(PieceKind::King) & self.colors(!color)).is_some() ||
-        (knight_attacks(square) & self.pieces(PieceKind::Knight) & self.colors(!color)).is_some() ||
-        (bishop_attacks(square, occupied) & (self.pieces(PieceKind::Bishop) | self.pieces(PieceKind::Queen)) & self.colors(!color)).is_some() ||
-        (rook_attacks(square, occupied) & (self.pieces(PieceKind::Rook) | self.pieces(PieceKind::Queen)) & self.colors(!color)).is_some()
-    }
-}
-
-impl Board {
-    pub const FEN_STARTPOS: &'static str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
-
-    pub const fn new() -> Self {
-        Self {
-            state: State::new(),
-            color: Color::White,
-            stack: Vec::new()
-        }
-    }
-
-    pub fn startpos() -> Result<Self, FenParseError> {
-        Self::from_fen(Self::FEN_STARTPOS)
-    }
-
-    pub fn from_fen(fen: &str) -> Result<Self, FenParseError> {
-        let mut board = Self::new();
-        let mut parts = fen.split(' ');
-
-        // Parse board
-        let rows = parts.next().ok_or(FenParseError::MissingBoard)?.split('/');
-
-        for (rank, row) in rows.rev().enumerate() {
-            if rank >= Rank::COUNT {
-                return Err(FenParseError::InvalidBoard);
-            }
-
-            let mut file = 0;
-
-            for c in row.chars() {
-                if file >= File::COUNT {
-                    return Err(FenParseError::InvalidBoard);
-                }
-
-                if let Some(digit) = c.to_digit(10) {
-                    file += digit as usize;
-                    continue;
-                }
-
-                let square = Square::new(Rank::from_raw(rank as u8), File::from_raw(file as u8));
-                let piece = Piece::from_char(c).ok_or(FenParseError::InvalidBoard)?;
-
-                board.state.place(square, piece);
-
-                file += 1;
-            }
-        }
-
-        // Parse color
-        board.color = match parts.next().ok_or(FenParseError::MissingColor) {
-            Ok("w") => Color::White,
-            Ok("b") => Color::Black,
-            _ => return Err(FenParseError::InvalidColor),
-        };
-
-        // Parse castling right
-        let castles = parts.next().ok_or(FenParseError::MissingCastle)?;
-
-        if castles.len() > 4 || castles.chars().all(|c| !"KQkq-".contains(c)) {
-            return Err(FenParseError::InvalidCastle);
-        }
-
-        if !castles.contains('K') {
-            board.state.castles.update(CastleKind::WhiteShort);
-        }
-
-        if !castles.contains('Q') {
-            board.state.castles.update(CastleKind::WhiteLong);
-        }
-
-        if !castles.contains('k') {
-            board.state.castles.update(CastleKind::BlackShort);
-        }
-
-        if !castles.contains('q') {
-            board.state.castles.update(CastleKind::BlackLong);
-        }
-
-        board.state.keys.main ^= ZOBRIST.castling[board.state.castles];
-
-        // Parse enpassant square
-        board.state.enpassant = match parts.next() {
-            Some(enpassant) => Square::from_str(enpassant),
-            None => None
-        };
-
-        // Parse halfmove counter
-        board.state.halfmove = parts.next().unwrap_or_default().parse().unwrap_or(0);
-
-        // Update threats
-        board.update_threats();
-
-        Ok(board)
-    }
-
-    pub fn make(&mut self, mv: Move) {
-        debug_assert!(self.is_pseudo_legal(mv));
-        debug_assert!(self.is_legal(mv));
-
-        // Push stack
-        self.stack.push(self.state.clone());
-
-        // Move data
-        let from = mv.from();
-        let to = mv.to();
-        let moving = self.state.at(from).unwrap();
-
-        // Remove enpassant square
-        if let Some(enpassant) = self.state.enpassant {
-            self.state.keys.main ^= ZOBRIST.enpassant[enpassant.file()];
-            self.state.enpassant = None;
-        }
-
-        // Update halfmove
-        self.state.halfmove += 1;
-
-        // Check capture
-        if self.state.at(to).is_some() {
-            self.state.halfmove = 0;
-            self.state.remove(to);
-        }
-
-        // Pawn move
-        if moving.kind() == PieceKind::Pawn {
-            self.state.halfmove = 0;
-
-            if from.rank().distance(to.rank()) == 2 {
-                self.state.enpassant = Some(to.enpassant());
-                self.state.keys.main ^= ZOBRIST.enpassant[to.file()];
-            }
-        }
-
-        // Update castling right
-        self.state.keys.main ^= ZOBRIST.castling[self.state.castles];
-
-        if moving.kind() == PieceKind::King {
-            if self.color == Color::White {
-                self.state.castles.update(CastleKind::WhiteShort);
-                self.state.castles.update(CastleKind::WhiteLong);
-            }
-            else {
-                self.state.castles.update(CastleKind::BlackShort);
-                self.state.castles.update(CastleKind::BlackLong);
-            }
-        }
-
-        if let Some(castle) = CastleKind::from_corner(from) {
-            self.state.castles.update(castle);
-        }
-
-        if let Some(castle) = CastleKind::from_corner(to) {
-            self.state.castles.update(castle);
-        }
-
-        self.state.keys.main ^= ZOBRIST.castling[self.state.castles];
-
-        // Move piece
-        self.state.remove(from);
-        self.state.place(
-            to,
-            match mv.promotion_kind() {
-                Some(promotion_kind) => Piece::new(promotion_kind, self.color),
-                _ => moving
-            }
-        );
-
-        if mv.is_castling() {
-            let castle = CastleKind::new(self.color, to > from);
-
-            self.state.remove(castle.rook_from());
-            self.state.place(castle.rook_to(), Piece::new(PieceKind::Rook, self.color));
-        }
-
-        if mv.is_enpassant() {
-            self.state.remove(to.enpassant());
-        }
-
-        // Flip side to move
-        self.color = !self.color;
-        self.state.keys.main ^= ZOBRIST.color;
-
-        // Update threat
-        self.update_threats();
-    }
-
-    pub fn unmake(&mut self) {
-        self.state = self.stack.pop().expect("can't unmake!");
-        self.color = !self.color;
-    }
-
-    pub fn update_threats(&mut self) {
-        self.state.checkers = self.state.attackers(self.state.king_square(self.color), self.state.occupied()) & self.state.colors(!self.color);
-
-        for color in [Color::White, Color::Black] {
-            self.state.blockers[color] = Bitboard::new();
-
-            let king_square = self.state.king_square(color);
-            let bishops = self.state.pieces(PieceKind::Bishop) | self.state.pieces(PieceKind::Queen);
-            let rooks = self.state.pieces(PieceKind::Rook) | self.state.pieces(PieceKind::Queen);
-
-            let mut snipers = Bitboard::new();
-
-            snipers |= bishop_attacks(king_square, Bitboard::new()) & bishops;
-            snipers |= rook_attacks(king_square, Bitboard::new()) & rooks;
-            snipers &= self.state.colors(!color);
-
-            let occupied = self.state.occupied() ^ snipers;
-
-            for square in snipers {
-                let ray = Bitboard::from_between(king_square, square) & occupied;
-
-                if ray.is_only() {
-                    self.state.blockers[color] |= ray;
-                }
-            }
-        }
-    }
-
-    #[inline(always)]
-    pub fn is_quiet(&self, mv: Move) -> bool {
-        !self.is_noisy(mv)
-    }
-
-    #[inline(always)]
-    pub fn is_noisy(&self, mv: Move) -> bool {
-        self.state.at(mv.to()).is_some() || mv.is_promotion() || mv.is_enpassant()
-    }
-
-    pub fn is_pseudo_legal(&self, mv: Move) -> bool {
-        if mv.is_null() {
-            return false;
-        }
-
-        let occupied = self.state.occupied();
-        let from = mv.from();
-        let to = mv.to();
-
-        let moving = match self.state.at(from) {
-            Some(piece) => piece,
-            None => return false
-        };
-
-        if self.state.colors(self.color).is_set(to) {
-            return false;
-        }
-        
-        if self.state.checkers().is_many() {
-            return mv.is_normal() && moving.kind() == PieceKind::King && king_attacks(from).is_set(to);
-        }
-
-        if mv.is_castling() {
-            let castle = match to {
-                Square::G1 => CastleKind::WhiteShort,
-                Square::C1 => CastleKind::WhiteLong,
-                Square::G8 => CastleKind::BlackShort,
-                Square::C8 => CastleKind::BlackLong,
-                _ => return false
-            };
-
-            if self.state.checkers().is_some() {
-                return false;
-            }
-
-            if !self.state.castles().is_allowed(castle) {
-                return false;
-            }
-
-            if (Bitboard::from_between(from, to) & occupied).is_some() {
-                return false;
-            }
-
-            return true;
-        }
-
-        if mv.is_enpassant() {
-            if moving.kind() != PieceKind::Pawn {
-                return false;
-            }
-
-            if !pawn_attacks(from, self.color).is_set(to) {
-                return false;
-            }
-
-            match self.state.enpassant() {
-                Some(square) => return to == square,
-                None => return false
-            }
-        }
-
-        if mv.is_promotion() && moving.kind() != PieceKind::Pawn {
-            return false;
-        }
-
-        if moving.kind() == PieceKind::King {
-            return king_attacks(from).is_set(to);
-        }
-
-        if self.state.checkers().is_some() {
-            let king_square = self.state.king_square(self.color);
-            let checker_square = self.state.checkers().lsb();
-            let betweens = Bitboard::from_between(king_square, checker_square) | self.state.checkers();
-
-            if !betweens.is_set(to) {
-                return false;
-            }
-        }
-
-        if moving.kind() == PieceKind::Pawn {
-            let north = match self.color {
-                Color::White => Direction::North,
-                Color::Black => Direction::South
-            };
-
-            let push_mask = match self.color {
-                Color::White => Bitboard::from_rank(Rank::Third),
-                Color::Black => Bitboard::from_rank(Rank::Sixth)
-            };
-
-            let push = Bitboard::from_square(from).shift(north) & !occupied;
-            let double_push = (push & push_mask).shift(north) & !occupied;
-            let capture = pawn_attacks(from, self.color) & self.state.colors(!self.color);
-
-            let mut span = push | double_push | capture;
-
-            if mv.is_promotion() {
-                span &= Bitboard::from_rank(Rank::First) | Bitboard::from_rank(Rank::Eighth);
-            }
-
-            return span.is_set(to);
-        }
-
-        if self.state.blockers(self.color).is_set(from) && !Bitboard::from_line(from, to).is_set(self.state.king_square(self.color)) {
-            return false;
-        }
-
-        let attacks = match moving.kind() {
-            PieceKind::Knight => knight_attacks(from),
-            PieceKind::Bishop => bishop_attacks(from, occupied),
-            PieceKind::Rook => rook_attacks(from, occupied),
-            PieceKind::Queen => bishop_attacks(from, occupied) | rook_attacks(from, occupied),
-            _ => Bitboard::new()
-        };
-
-        return attacks.is_set(to);
-    }
-
-    pub fn is_legal(&self, mv: Move) -> bool {
-        debug_assert!(mv.is_some());
-
-        let from = mv.from();
-        let to = mv.to();
-        let kind = self.state.at(from).unwrap().kind();
-
-        if kind != PieceKind::King && kind != PieceKind::Pawn {
-            return true;
-        }
-
-        if mv.is_castling() {
-            let castle = CastleKind::new(self.color, to > from);
-
-            return
-                !self.state.is_attacked(castle.king_to(), self.color, self.state.occupied()) &&
-                !self.state.is_attacked(castle.rook_to(), self.color, self.state.occupied());
-        }
-
-        if kind == PieceKind::King {
-            return !self.state.is_attacked(to, self.color, self.state.occupied() ^ Bitboard::from_square(from));
-        }
-
-        if mv.is_enpassant() {
-            if self.state.enpassant().is_some() {
-                let mut occupied = self.state.occupied();
-
-                occupied.clear(from);
-                occupied.set(to);
-
-                occupied ^= Bitboard::from_square(to).shift(
-                    match self.color {
-                        Color::White => Direction::South,
-                        Color::Black => Direction::North
-                    }
-                );
-
-                let king_square = self.state.king_square(self.color);
-                let bishops = self.state.pieces(PieceKind::Bishop) | self.state.pieces(PieceKind::Queen);
-                let rooks = self.state.pieces(PieceKind::Rook) | self.state.pieces(PieceKind::Queen);
-
-                return
-                    (bishop_attacks(king_square, occupied) & self.state.colors(!self.color) & bishops).is_empty() &&
-                    (rook_attacks(king_square, occupied) & self.state.colors(!self.color) & rooks).is_empty();
-            }
-            else {
-                return false;
-            }
-        }
-
-        if !self.state.blockers(self.color).is_set(from) {
-            return true;
-        }
-
-        if (Bitboard::from_line(from, to) & self.state.pieces(PieceKind::King) & self.state.colors(self.color)).is_some() {
-            return true;
-        }
-
-        false
-    }
-}
-
-impl std::fmt::Display for Board {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for rank in Rank::all().rev() {
-            for file in File::all() {
-                if let Some(piece) = self.state.at(Square::new(rank, file)) {
-                    write!(f, "{} ", piece)?;
-                }
-                else {
-                    write!(f, ". ")?;
-                }
-            }
-
-            write!(f, "\n")?;
-        }
-
-        writeln!(f, "color: {}", self.color)?;
-
-        Ok(())
-    }
+use std::cell::Cell;
+
+use crate::chess::{attack::{bishop_attacks, king_attacks, knight_attacks, pawn_attacks, rook_attacks}, bitboard::Bitboard, castle::{Castle, CastleKind}, color::Color, direction::Direction, file::File, moves::Move, piece::{Piece, PieceKind}, rank::Rank, result::GameResult, square::Square, zobrist::ZOBRIST};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FenParseError {
+    MissingBoard,
+    MissingColor,
+    MissingCastle,
+    InvalidBoard,
+    InvalidColor,
+    InvalidCastle
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Key {
+    pub main: u64
+}
+
+#[derive(Debug, Clone)]
+pub struct State {
+    pieces: [Bitboard; PieceKind::COUNT],
+    colors: [Bitboard; Color::COUNT],
+    mailbox: [Option<Piece>; Square::COUNT],
+    castles: Castle,
+    enpassant: Option<Square>,
+    halfmove: u8,
+    fullmove: u16,
+    checkers: Bitboard,
+    blockers: [Bitboard; Color::COUNT],
+    // Attack maps of each kind, filled the first time a color's are asked for and cleared whenever a piece is placed or removed
+    attacks: [Cell<Option<[Bitboard; PieceKind::COUNT]>>; Color::COUNT],
+    keys: Key
+}
+
+#[derive(Debug, Clone)]
+pub struct Board {
+    pub state: State,
+    pub color: Color,
+    pub stack: Vec<State>
+}
+
+impl State {
+    pub const fn new() -> Self {
+        Self {
+            pieces: [Bitboard::new(); PieceKind::COUNT],
+            colors: [Bitboard::new(); Color::COUNT],
+            mailbox: [None; Square::COUNT],
+            castles: Castle::new(),
+            enpassant: None,
+            halfmove: 0,
+            fullmove: 1,
+            checkers: Bitboard::new(),
+            blockers: [Bitboard::new(); Color::COUNT],
+            attacks: [const { Cell::new(None) }; Color::COUNT],
+            keys: Key { main: 0 }
+        }
+    }
+
+    #[inline(always)]
+    pub fn pieces(&self, kind: PieceKind) -> Bitboard {
+        self.pieces[kind]
+    }
+
+    #[inline(always)]
+    pub fn colors(&self, color: Color) -> Bitboard {
+        self.colors[color]
+    }
+
+    #[inline(always)]
+    pub fn at(&self, square: Square) -> Option<Piece> {
+        self.mailbox[square]
+    }
+
+    #[inline(always)]
+    pub fn occupied(&self) -> Bitboard {
+        self.colors[Color::White] | self.colors[Color::Black]
+    }
+
+    #[inline(always)]
+    pub const fn castles(&self) -> Castle {
+        self.castles
+    }
+
+    #[inline(always)]
+    pub const fn enpassant(&self) -> Option<Square> {
+        self.enpassant
+    }
+
+    #[inline(always)]
+    pub const fn halfmove(&self) -> u8 {
+        self.halfmove
+    }
+
+    // Starts at 1 and goes up after every black move
+    #[inline(always)]
+    pub const fn fullmove(&self) -> u16 {
+        self.fullmove
+    }
+
+    #[inline(always)]
+    pub const fn checkers(&self) -> Bitboard {
+        self.checkers
+    }
+
+    #[inline(always)]
+    pub fn blockers(&self, color: Color) -> Bitboard {
+        self.blockers[color]
+    }
+
+    // Every square attacked by the color
+    #[inline(always)]
+    pub fn attacks(&self, color: Color) -> Bitboard {
+        self.attacks_up_to(color, PieceKind::King)
+    }
+
+    // Worked out when first asked for, so making a move doesn't pay for maps most positions never look at
+    #[inline(always)]
+    pub fn attacks_by(&self, color: Color, kind: PieceKind) -> Bitboard {
+        self.attack_maps(color)[kind]
+    }
+
+    // Squares attacked by the color's pieces of this kind or cheaper, e.g. rooks and below
+    pub fn attacks_up_to(&self, color: Color, kind: PieceKind) -> Bitboard {
+        self.attack_maps(color)[..=kind.index()].iter().fold(Bitboard::new(), |attacks, &map| attacks | map)
+    }
+
+    fn attack_maps(&self, color: Color) -> [Bitboard; PieceKind::COUNT] {
+        if let Some(maps) = self.attacks[color].get() {
+            return maps;
+        }
+
+        let mut maps = [Bitboard::new(); PieceKind::COUNT];
+
+        for kind in PieceKind::all() {
+            maps[kind] = self.compute_attacks(color, kind);
+        }
+
+        self.attacks[color].set(Some(maps));
+
+        maps
+    }
+
+    fn compute_attacks(&self, color: Color, kind: PieceKind) -> Bitboard {
+        let pieces = self.pieces(kind) & self.colors(color);
+
+        if kind == PieceKind::Pawn {
+            return match color {
+                Color::White => pieces.shift(Direction::NorthEast) | pieces.shift(Direction::NorthWest),
+                Color::Black => pieces.shift(Direction::SouthEast) | pieces.shift(Direction::SouthWest)
+            };
+        }
+
+        let occupied = self.occupied();
+        let mut attacks = Bitboard::new();
+
+        for square in pieces {
+            attacks |= match kind {
+                PieceKind::Knight => knight_attacks(square),
+                PieceKind::Bishop => bishop_attacks(square, occupied),
+                PieceKind::Rook => rook_attacks(square, occupied),
+                PieceKind::Queen => bishop_attacks(square, occupied) | rook_attacks(square, occupied),
+                _ => king_attacks(square)
+            };
+        }
+
+        attacks
+    }
+
+    #[inline(always)]
+    pub const fn key(&self) -> Key {
+        self.keys
+    }
+
+    #[inline(always)]
+    pub fn king_square(&self, color: Color) -> Square {
+        (self.pieces(PieceKind::King) & self.colors(color)).lsb()
+    }
+
+    pub fn attackers(&self, square: Square, occupied: Bitboard) -> Bitboard {
+        let mut attackers = Bitboard::new();
+
+        attackers |= pawn_attacks(square, Color::White) & self.colors(Color::Black) & self.pieces(PieceKind::Pawn);
+        attackers |= pawn_attacks(square, Color::Black) & self.colors(Color::White) & self.pieces(PieceKind::Pawn);
+        attackers |= king_attacks(square) & self.pieces(PieceKind::King);
+        attackers |= knight_attacks(square) & self.pieces(PieceKind::Knight);
+        attackers |= bishop_attacks(square, occupied) & (self.pieces(PieceKind::Bishop) | self.pieces(PieceKind::Queen));
+        attackers |= rook_attacks(square, occupied) & (self.pieces(PieceKind::Rook) | self.pieces(PieceKind::Queen));
+
+        attackers
+    }
+
+    pub fn place(&mut self, square: Square, piece: Piece) {
+        self.pieces[piece.kind()].set(square);
+        self.colors[piece.color()].set(square);
+        self.mailbox[square] = Some(piece);
+        self.keys.main ^= ZOBRIST.piece[piece][square];
+        self.attacks = Default::default();
+    }
+
+    pub fn remove(&mut self, square: Square) {
+        if let Some(piece) = self.mailbox[square] {
+            self.pieces[piece.kind()].clear(square);
+            self.colors[piece.color()].clear(square);
+            self.mailbox[square] = None;
+            self.keys.main ^= ZOBRIST.piece[piece][square];
+            self.attacks = Default::default();
+        }
+    }
+
+    pub fn is_attacked(&self, square: Square, color: Color, occupied: Bitboard) -> bool {
+        (pawn_attacks(square, color) & self.pieces(PieceKind::Pawn) & self.colors(!color)).is_some() ||
+        (king_attacks(square) & self.pieces(PieceKind::King) & self.colors(!color)).is_some() ||
+        (knight_attacks(square) & self.pieces(PieceKind::Knight) & self.colors(!color)).is_some() ||
+        (bishop_attacks(square, occupied) & (self.pieces(PieceKind::Bishop) | self.pieces(PieceKind::Queen)) & self.colors(!color)).is_some() ||
+        (rook_attacks(square, occupied) & (self.pieces(PieceKind::Rook) | self.pieces(PieceKind::Queen)) & self.colors(!color)).is_some()
+    }
+}
+
+impl Board {
+    pub const FEN_STARTPOS: &'static str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+    pub const fn new() -> Self {
+        Self {
+            state: State::new(),
+            color: Color::White,
+            stack: Vec::new()
+        }
+    }
+
+    pub fn startpos() -> Result<Self, FenParseError> {
+        Self::from_fen(Self::FEN_STARTPOS)
+    }
+
+    pub fn from_fen(fen: &str) -> Result<Self, FenParseError> {
+        let mut board = Self::new();
+        let mut parts = fen.split(' ');
+
+        // Parse board
+        let rows = parts.next().ok_or(FenParseError::MissingBoard)?.split('/');
+
+        for (rank, row) in rows.rev().enumerate() {
+            if rank >= Rank::COUNT {
+                return Err(FenParseError::InvalidBoard);
+            }
+
+            let mut file = 0;
+
+            for c in row.chars() {
+                if file >= File::COUNT {
+                    return Err(FenParseError::InvalidBoard);
+                }
+
+                if let Some(digit) = c.to_digit(10) {
+                    file += digit as usize;
+                    continue;
+                }
+
+                let square = Square::new(Rank::from_raw(rank as u8), File::from_raw(file as u8));
+                let piece = Piece::from_char(c).ok_or(FenParseError::InvalidBoard)?;
+
+                board.state.place(square, piece);
+
+                file += 1;
+            }
+        }
+
+        // Parse color
+        board.color = match parts.next().ok_or(FenParseError::MissingColor) {
+            Ok("w") => Color::White,
+            Ok("b") => Color::Black,
+            _ => return Err(FenParseError::InvalidColor),
+        };
+
+        // Parse castling right
+        let castles = parts.next().ok_or(FenParseError::MissingCastle)?;
+
+        if castles.len() > 4 || castles.chars().all(|c| !"KQkq-".contains(c)) {
+            return Err(FenParseError::InvalidCastle);
+        }
+
+        if !castles.contains('K') {
+            board.state.castles.update(CastleKind::WhiteShort);
+        }
+
+        if !castles.contains('Q') {
+            board.state.castles.update(CastleKind::WhiteLong);
+        }
+
+        if !castles.contains('k') {
+            board.state.castles.update(CastleKind::BlackShort);
+        }
+
+        if !castles.contains('q') {
+            board.state.castles.update(CastleKind::BlackLong);
+        }
+
+        board.state.keys.main ^= ZOBRIST.castling[board.state.castles];
+
+        // Parse enpassant square
+        board.state.enpassant = match parts.next() {
+            Some(enpassant) => Square::from_str(enpassant),
+            None => None
+        };
+
+        if let Some(enpassant) = board.state.enpassant {
+            board.state.keys.main ^= ZOBRIST.enpassant[enpassant.file()];
+        }
+
+        if board.color == Color::Black {
+            board.state.keys.main ^= ZOBRIST.color;
+        }
+
+        // Parse halfmove counter
+        board.state.halfmove = parts.next().unwrap_or_default().parse().unwrap_or(0);
+
+        // Parse fullmove counter
+        board.state.fullmove = parts.next().unwrap_or_default().parse().unwrap_or(1).max(1);
+
+        // Update threats
+        board.update_threats();
+
+        Ok(board)
+    }
+
+    pub fn to_fen(&self) -> String {
+        let mut fen = String::new();
+
+        // Write board
+        for rank in Rank::all().rev() {
+            let mut empty = 0;
+
+            for file in File::all() {
+                match self.state.at(Square::new(rank, file)) {
+                    Some(piece) => {
+                        if empty > 0 {
+                            fen.push_str(&empty.to_string());
+                            empty = 0;
+                        }
+
+                        fen.push_str(&piece.to_string());
+                    },
+                    None => empty += 1
+                }
+            }
+
+            if empty > 0 {
+                fen.push_str(&empty.to_string());
+            }
+
+            if rank != Rank::First {
+                fen.push('/');
+            }
+        }
+
+        // Write color
+        fen.push_str(&format!(" {} ", self.color));
+
+        // Write castling right
+        let castles: String = CastleKind::all()
+            .zip("KQkq".chars())
+            .filter(|&(castle, _)| self.state.castles().is_allowed(castle))
+            .map(|(_, c)| c)
+            .collect();
+
+        fen.push_str(if castles.is_empty() { "-" } else { &castles });
+
+        // Write enpassant square and move counters
+        match self.state.enpassant() {
+            Some(square) => fen.push_str(&format!(" {} {} {}", square, self.state.halfmove(), self.state.fullmove())),
+            None => fen.push_str(&format!(" - {} {}", self.state.halfmove(), self.state.fullmove()))
+        }
+
+        fen
+    }
+
+    pub fn make(&mut self, mv: Move) {
+        debug_assert!(self.is_pseudo_legal(mv));
+        debug_assert!(self.is_legal(mv));
+
+        // Push stack
+        self.stack.push(self.state.clone());
+
+        // Move data
+        let from = mv.from();
+        let to = mv.to();
+        let moving = self.state.at(from).unwrap();
+
+        // Remove enpassant square
+        if let Some(enpassant) = self.state.enpassant {
+            self.state.keys.main ^= ZOBRIST.enpassant[enpassant.file()];
+            self.state.enpassant = None;
+        }
+
+        // Update halfmove
+        self.state.halfmove += 1;
+
+        // Check capture
+        if self.state.at(to).is_some() {
+            self.state.halfmove = 0;
+            self.state.remove(to);
+        }
+
+        // Pawn move
+        if moving.kind() == PieceKind::Pawn {
+            self.state.halfmove = 0;
+
+            if from.rank().distance(to.rank()) == 2 {
+                self.state.enpassant = Some(to.enpassant());
+                self.state.keys.main ^= ZOBRIST.enpassant[to.file()];
+            }
+        }
+
+        // Update castling right
+        self.state.keys.main ^= ZOBRIST.castling[self.state.castles];
+
+        if moving.kind() == PieceKind::King {
+            if self.color == Color::White {
+                self.state.castles.update(CastleKind::WhiteShort);
+                self.state.castles.update(CastleKind::WhiteLong);
+            }
+            else {
+                self.state.castles.update(CastleKind::BlackShort);
+                self.state.castles.update(CastleKind::BlackLong);
+            }
+        }
+
+        if let Some(castle) = CastleKind::from_corner(from) {
+            self.state.castles.update(castle);
+        }
+
+        if let Some(castle) = CastleKind::from_corner(to) {
+            self.state.castles.update(castle);
+        }
+
+        self.state.keys.main ^= ZOBRIST.castling[self.state.castles];
+
+        // Move piece
+        self.state.remove(from);
+        self.state.place(
+            to,
+            match mv.promotion_kind() {
+                Some(promotion_kind) => Piece::new(promotion_kind, self.color),
+                _ => moving
+            }
+        );
+
+        if mv.is_castling() {
+            let castle = CastleKind::new(self.color, to > from);
+
+            self.state.remove(castle.rook_from());
+            self.state.place(castle.rook_to(), Piece::new(PieceKind::Rook, self.color));
+        }
+
+        if mv.is_enpassant() {
+            self.state.remove(to.enpassant());
+        }
+
+        // Flip side to move
+        if self.color == Color::Black {
+            self.state.fullmove += 1;
+        }
+
+        self.color = !self.color;
+        self.state.keys.main ^= ZOBRIST.color;
+
+        // Update threat
+        self.update_threats();
+    }
+
+    pub fn unmake(&mut self) {
+        self.state = self.stack.pop().expect("can't unmake!");
+        self.color = !self.color;
+    }
+
+    // Passes the turn, never while in check
+    pub fn make_null(&mut self) {
+        debug_assert!(self.state.checkers.is_empty());
+
+        self.stack.push(self.state.clone());
+
+        if let Some(enpassant) = self.state.enpassant {
+            self.state.keys.main ^= ZOBRIST.enpassant[enpassant.file()];
+            self.state.enpassant = None;
+        }
+
+        self.state.halfmove += 1;
+
+        if self.color == Color::Black {
+            self.state.fullmove += 1;
+        }
+
+        // Pieces don't move, so only the checkers of the side to move change and there are none
+        self.color = !self.color;
+        self.state.keys.main ^= ZOBRIST.color;
+    }
+
+    pub fn unmake_null(&mut self) {
+        self.unmake();
+    }
+
+    pub fn update_threats(&mut self) {
+        self.state.checkers = self.state.attackers(self.state.king_square(self.color), self.state.occupied()) & self.state.colors(!self.color);
+
+        for color in [Color::White, Color::Black] {
+            self.state.blockers[color] = Bitboard::new();
+
+            let king_square = self.state.king_square(color);
+            let bishops = self.state.pieces(PieceKind::Bishop) | self.state.pieces(PieceKind::Queen);
+            let rooks = self.state.pieces(PieceKind::Rook) | self.state.pieces(PieceKind::Queen);
+
+            let mut snipers = Bitboard::new();
+
+            snipers |= bishop_attacks(king_square, Bitboard::new()) & bishops;
+            snipers |= rook_attacks(king_square, Bitboard::new()) & rooks;
+            snipers &= self.state.colors(!color);
+
+            let occupied = self.state.occupied() ^ snipers;
+
+            for square in snipers {
+                let ray = Bitboard::from_between(king_square, square) & occupied;
+
+                if ray.is_only() {
+                    self.state.blockers[color] |= ray;
+                }
+            }
+        }
+    }
+
+    #[inline(always)]
+    pub fn is_quiet(&self, mv: Move) -> bool {
+        !self.is_noisy(mv)
+    }
+
+    #[inline(always)]
+    pub fn is_noisy(&self, mv: Move) -> bool {
+        self.state.at(mv.to()).is_some() || mv.is_promotion() || mv.is_enpassant()
+    }
+
+    pub fn is_pseudo_legal(&self, mv: Move) -> bool {
+        if mv.is_null() || !mv.is_valid() {
+            return false;
+        }
+
+        let occupied = self.state.occupied();
+        let from = mv.from();
+        let to = mv.to();
+
+        let moving = match self.state.at(from) {
+            Some(piece) if piece.color() == self.color => piece,
+            _ => return false
+        };
+
+        if self.state.colors(self.color).is_set(to) {
+            return false;
+        }
+        
+        if self.state.checkers().is_many() {
+            return mv.is_normal() && moving.kind() == PieceKind::King && king_attacks(from).is_set(to);
+        }
+
+        if mv.is_castling() {
+            let castle = match to {
+                Square::G1 => CastleKind::WhiteShort,
+                Square::C1 => CastleKind::WhiteLong,
+                Square::G8 => CastleKind::BlackShort,
+                Square::C8 => CastleKind::BlackLong,
+                _ => return false
+            };
+
+            if moving.kind() != PieceKind::King || castle != CastleKind::new(self.color, castle.is_short()) {
+                return false;
+            }
+
+            if self.state.checkers().is_some() {
+                return false;
+            }
+
+            if !self.state.castles().is_allowed(castle) {
+                return false;
+            }
+
+            if (Bitboard::from_between(from, castle.rook_from()) & occupied).is_some() {
+                return false;
+            }
+
+            return true;
+        }
+
+        if mv.is_enpassant() {
+            if moving.kind() != PieceKind::Pawn {
+                return false;
+            }
+
+            if !pawn_attacks(from, self.color).is_set(to) {
+                return false;
+            }
+
+            match self.state.enpassant() {
+                Some(square) => return to == square,
+                None => return false
+            }
+        }
+
+        if mv.is_promotion() && moving.kind() != PieceKind::Pawn {
+            return false;
+        }
+
+        if moving.kind() == PieceKind::King {
+            return king_attacks(from).is_set(to);
+        }
+
+        if self.state.checkers().is_some() {
+            let king_square = self.state.king_square(self.color);
+            let checker_square = self.state.checkers().lsb();
+            let betweens = Bitboard::from_between(king_square, checker_square) | self.state.checkers();
+
+            if !betweens.is_set(to) {
+                return false;
+            }
+        }
+
+        if moving.kind() == PieceKind::Pawn {
+            let north = match self.color {
+                Color::White => Direction::North,
+                Color::Black => Direction::South
+            };
+
+            let push_mask = match self.color {
+                Color::White => Bitboard::from_rank(Rank::Third),
+                Color::Black => Bitboard::from_rank(Rank::Sixth)
+            };
+
+            let push = Bitboard::from_square(from).shift(north) & !occupied;
+            let double_push = (push & push_mask).shift(north) & !occupied;
+            let capture = pawn_attacks(from, self.color) & self.state.colors(!self.color);
+
+            let mut span = push | double_push | capture;
+
+            if mv.is_promotion() {
+                span &= Bitboard::from_rank(Rank::First) | Bitboard::from_rank(Rank::Eighth);
+            }
+            else {
+                span &= !(Bitboard::from_rank(Rank::First) | Bitboard::from_rank(Rank::Eighth));
+            }
+
+            return span.is_set(to);
+        }
+
+        if self.state.blockers(self.color).is_set(from) && !Bitboard::from_line(from, to).is_set(self.state.king_square(self.color)) {
+            return false;
+        }
+
+        let attacks = match moving.kind() {
+            PieceKind::Knight => knight_attacks(from),
+            PieceKind::Bishop => bishop_attacks(from, occupied),
+            PieceKind::Rook => rook_attacks(from, occupied),
+            PieceKind::Queen => bishop_attacks(from, occupied) | rook_attacks(from, occupied),
+            _ => Bitboard::new()
+        };
+
+        attacks.is_set(to)
+    }
+
+    pub fn is_legal(&self, mv: Move) -> bool {
+        debug_assert!(mv.is_some());
+
+        let from = mv.from();
+        let to = mv.to();
+        let kind = self.state.at(from).unwrap().kind();
+
+        if kind != PieceKind::King && kind != PieceKind::Pawn {
+            return true;
+        }
+
+        if mv.is_castling() {
+            let castle = CastleKind::new(self.color, to > from);
+
+            return
+                !self.state.is_attacked(castle.king_to(), self.color, self.state.occupied()) &&
+                !self.state.is_attacked(castle.rook_to(), self.color, self.state.occupied());
+        }
+
+        if kind == PieceKind::King {
+            return !self.state.is_attacked(to, self.color, self.state.occupied() ^ Bitboard::from_square(from));
+        }
+
+        if mv.is_enpassant() {
+            if self.state.enpassant().is_some() {
+                let mut occupied = self.state.occupied();
+
+                occupied.clear(from);
+                occupied.set(to);
+
+                occupied ^= Bitboard::from_square(to).shift(
+                    match self.color {
+                        Color::White => Direction::South,
+                        Color::Black => Direction::North
+                    }
+                );
+
+                let king_square = self.state.king_square(self.color);
+                let bishops = self.state.pieces(PieceKind::Bishop) | self.state.pieces(PieceKind::Queen);
+                let rooks = self.state.pieces(PieceKind::Rook) | self.state.pieces(PieceKind::Queen);
+
+                return
+                    (bishop_attacks(king_square, occupied) & self.state.colors(!self.color) & bishops).is_empty() &&
+                    (rook_attacks(king_square, occupied) & self.state.colors(!self.color) & rooks).is_empty();
+            }
+            else {
+                return false;
+            }
+        }
+
+        if !self.state.blockers(self.color).is_set(from) {
+            return true;
+        }
+
+        if (Bitboard::from_line(from, to) & self.state.pieces(PieceKind::King) & self.state.colors(self.color)).is_some() {
+            return true;
+        }
+
+        false
+    }
+
+    // Fifty move rule, repetition since the last irreversible move and bare minors
+    pub fn is_draw(&self) -> bool {
+        if self.state.halfmove >= 100 {
+            return true;
+        }
+
+        let key = self.state.key();
+
+        let repetition = self.stack
+            .iter()
+            .rev()
+            .take(self.state.halfmove as usize)
+            .skip(1)
+            .step_by(2)
+            .any(|state| state.key() == key);
+
+        repetition || self.is_insufficient_material()
+    }
+
+    // Result once the game is over by mate, stalemate or a draw rule
+    pub fn outcome(&self) -> Option<GameResult> {
+        if !self.generate_moves().iter_moves().any(|&mv| self.is_legal(mv)) {
+            return Some(match (self.state.checkers.is_some(), self.color) {
+                (false, _) => GameResult::Draw,
+                (true, Color::White) => GameResult::BlackWin,
+                (true, Color::Black) => GameResult::WhiteWin
+            });
+        }
+
+        self.is_draw().then_some(GameResult::Draw)
+    }
+
+    pub fn is_insufficient_material(&self) -> bool {
+        let heavy = self.state.pieces(PieceKind::Pawn) | self.state.pieces(PieceKind::Rook) | self.state.pieces(PieceKind::Queen);
+
+        heavy.is_empty() && self.state.occupied().count() <= 3
+    }
+}
+
+impl std::fmt::Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for rank in Rank::all().rev() {
+            for file in File::all() {
+                if let Some(piece) = self.state.at(Square::new(rank, file)) {
+                    write!(f, "{} ", piece)?;
+                }
+                else {
+                    write!(f, ". ")?;
+                }
+            }
+
+            writeln!(f)?;
+        }
+
+        writeln!(f, "color: {}", self.color)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chess::random::Random;
+
+    #[test]
+    fn attack_maps() {
+        let mut random = Random::new(32);
+
+        for _ in 0..20 {
+            let mut board = Board::startpos().unwrap();
+
+            for _ in 0..200 {
+                let occupied = board.state.occupied();
+
+                for color in [Color::White, Color::Black] {
+                    for square in Square::all() {
+                        let attackers = board.state.attackers(square, occupied) & board.state.colors(color);
+
+                        assert_eq!(board.state.attacks(color).is_set(square), attackers.is_some());
+
+                        for kind in [PieceKind::Pawn, PieceKind::Knight, PieceKind::Bishop, PieceKind::Rook, PieceKind::Queen, PieceKind::King] {
+                            assert_eq!(board.state.attacks_by(color, kind).is_set(square), (attackers & board.state.pieces(kind)).is_some());
+                        }
+                    }
+                }
+
+                let moves: Vec<Move> = board.generate_moves().iter_moves().copied().filter(|&mv| board.is_legal(mv)).collect();
+
+                if moves.is_empty() {
+                    break;
+                }
+
+                board.make(moves[random.below(moves.len())]);
+            }
+        }
+    }
+
+    #[test]
+    fn cached_attacks() {
+        let mut random = Random::new(35);
+        let mut board = Board::startpos().unwrap();
+
+        // Maps cached before a move must be dropped by make and come back with the old state on unmake
+        let matches = |board: &Board| {
+            let fresh = Board::from_fen(&board.to_fen()).unwrap();
+
+            [Color::White, Color::Black].into_iter().all(|color| PieceKind::all().all(|kind| {
+                board.state.attacks_by(color, kind) == fresh.state.compute_attacks(color, kind)
+            }))
+        };
+
+        for _ in 0..200 {
+            let moves: Vec<Move> = board.generate_moves().iter_moves().copied().filter(|&mv| board.is_legal(mv)).collect();
+
+            if moves.is_empty() {
+                break;
+            }
+
+            assert!(matches(&board));
+            assert!(board.state.attacks.iter().all(|maps| maps.get().is_some()));
+
+            let mv = moves[random.below(moves.len())];
+
+            board.make(mv);
+            assert!(board.state.attacks.iter().all(|maps| maps.get().is_none()));
+            assert!(matches(&board));
+
+            board.unmake();
+            assert!(matches(&board));
+
+            board.make(mv);
+        }
+    }
+
+    #[test]
+    fn draws() {
+        let mut board = Board::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 0 1").unwrap();
+
+        assert!(!board.is_draw());
+
+        for uci in ["a1a2", "e8d8", "a2a1", "d8e8"] {
+            let mv = board.generate_moves().iter_moves().copied().find(|mv| mv.to_string() == uci).unwrap();
+
+            board.make(mv);
+        }
+
+        assert!(board.is_draw());
+
+        assert!(Board::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 100 80").unwrap().is_draw());
+        assert!(Board::from_fen("4k3/8/8/8/8/8/8/2B1K3 w - - 0 1").unwrap().is_draw());
+        assert!(!Board::from_fen("4k3/8/8/8/8/8/8/1NB1K3 w - - 0 1").unwrap().is_draw());
+    }
+
+    #[test]
+    fn outcomes() {
+        assert_eq!(Board::startpos().unwrap().outcome(), None);
+        assert_eq!(Board::from_fen("R5k1/5ppp/8/8/8/8/5PPP/6K1 b - - 1 1").unwrap().outcome(), Some(GameResult::WhiteWin));
+        assert_eq!(Board::from_fen("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1").unwrap().outcome(), Some(GameResult::Draw));
+        assert_eq!(Board::from_fen("4k3/8/8/8/8/8/8/2B1K3 w - - 0 1").unwrap().outcome(), Some(GameResult::Draw));
+    }
+
+    #[test]
+    fn null_move() {
+        let mut board = Board::from_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1").unwrap();
+        let key = board.state.key();
+
+        board.make_null();
+
+        assert_eq!(board.color, Color::Black);
+        assert_eq!(board.state.key(), Board::from_fen("4k3/8/8/3pP3/8/8/8/4K3 b - - 1 1").unwrap().state.key());
+
+        board.unmake_null();
+
+        assert_eq!(board.color, Color::White);
+        assert_eq!(board.state.key(), key);
+    }
+
+    #[test]
+    fn fullmove() {
+        let mut board = Board::from_fen("4k3/8/8/8/8/8/8/R3K3 b - - 7 41").unwrap();
+
+        assert_eq!(board.state.fullmove(), 41);
+
+        for uci in ["e8d8", "a1a2", "d8e8"] {
+            let mv = board.generate_moves().iter_moves().copied().find(|mv| mv.to_string() == uci).unwrap();
+
+            board.make(mv);
+        }
+
+        assert_eq!(board.to_fen(), "4k3/8/8/8/8/8/R7/4K3 w - - 10 43");
+
+        board.unmake();
+
+        assert_eq!(board.to_fen(), "3k4/8/8/8/8/8/R7/4K3 b - - 9 42");
+        assert_eq!(Board::from_fen("4k3/8/8/8/8/8/8/R3K3 w -").unwrap().state.fullmove(), 1);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,241 @@
+use crate::chess::{board::Board, castle::CastleKind, color::Color, moves::Move, piece::{Piece, PieceKind}, random::Random, rank::Rank, reference::Mailbox, square::Square};
+
+const START_FENS: [&str; 5] = [
+    Board::FEN_STARTPOS,
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+    "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8"
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Discrepancy {
+    pub fen: String,
+    pub message: String
+}
+
+impl std::fmt::Display for Discrepancy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.fen, self.message)
+    }
+}
+
+fn sorted(moves: impl Iterator<Item = Move>) -> Vec<u16> {
+    let mut moves: Vec<u16> = moves.map(Move::value).collect();
+
+    moves.sort_unstable();
+    moves
+}
+
+// Compares the move generator, the move validators and make/unmake of one position against the reference generator
+pub fn check(board: &mut Board) -> Result<(), String> {
+    let generated = sorted(board.generate_moves().iter_moves().copied());
+
+    if generated.windows(2).any(|pair| pair[0] == pair[1]) {
+        return Err("duplicate generated move".to_string());
+    }
+
+    let quiet = board.generate_quiet_moves();
+    let noisy = board.generate_noisy_moves();
+
+    if sorted(quiet.iter_moves().chain(noisy.iter_moves()).copied()) != generated {
+        return Err("quiet and noisy moves don't add up to all moves".to_string());
+    }
+
+    if let Some(mv) = quiet.iter_moves().find(|&&mv| board.is_noisy(mv)) {
+        return Err(format!("quiet move {} is noisy", mv));
+    }
+
+    if let Some(mv) = noisy.iter_moves().find(|&&mv| board.is_quiet(mv)) {
+        return Err(format!("noisy move {} is quiet", mv));
+    }
+
+    let legal = sorted(board.generate_moves().iter_moves().copied().filter(|&mv| board.is_legal(mv)));
+    let reference = sorted(Mailbox::from_board(board).legal_moves().into_iter());
+
+    if legal != reference {
+        let missing: Vec<String> = reference.iter().filter(|mv| !legal.contains(mv)).map(|&mv| Move::from_raw(mv).to_string()).collect();
+        let extra: Vec<String> = legal.iter().filter(|mv| !reference.contains(mv)).map(|&mv| Move::from_raw(mv).to_string()).collect();
+
+        return Err(format!("legal moves differ, missing [{}] extra [{}]", missing.join(" "), extra.join(" ")));
+    }
+
+    for raw in 0..=u16::MAX {
+        let mv = Move::from_raw(raw);
+        let pseudo_legal = board.is_pseudo_legal(mv);
+
+        if pseudo_legal != generated.binary_search(&raw).is_ok() {
+            return Err(format!("is_pseudo_legal({:#06x}) returns {}", raw, pseudo_legal));
+        }
+
+        if pseudo_legal && board.is_legal(mv) != reference.binary_search(&raw).is_ok() {
+            return Err(format!("is_legal({}) returns {}", mv, board.is_legal(mv)));
+        }
+    }
+
+    let key = board.state.key();
+    let fen = board.to_fen();
+
+    for &raw in reference.iter() {
+        let mv = Move::from_raw(raw);
+
+        board.make(mv);
+
+        let expected = Board::from_fen(&board.to_fen()).unwrap().state.key();
+
+        if board.state.key() != expected {
+            return Err(format!("key after {} is {:#018x}, expected {:#018x}", mv, board.state.key().main, expected.main));
+        }
+
+        board.unmake();
+
+        if board.state.key() != key || board.to_fen() != fen {
+            return Err(format!("unmake of {} doesn't restore the position", mv));
+        }
+    }
+
+    Ok(())
+}
+
+// Positions the shrinker may produce must still be reachable looking, otherwise both generators are free to disagree
+fn is_sane(board: &Board) -> bool {
+    for color in [Color::White, Color::Black] {
+        if (board.state.pieces(PieceKind::King) & board.state.colors(color)).count() != 1 {
+            return false;
+        }
+    }
+
+    if Square::all().any(|square| board.state.at(square).is_some_and(|piece| piece.kind() == PieceKind::Pawn) && (square.rank() == Rank::First || square.rank() == Rank::Eighth)) {
+        return false;
+    }
+
+    !board.state.is_attacked(board.state.king_square(!board.color), !board.color, board.state.occupied())
+}
+
+// Removes castling rights and the enpassant square when the pieces that justify them are gone
+fn sanitize(board: &Board) -> String {
+    let fen = board.to_fen();
+    let mut fields: Vec<String> = fen.split(' ').map(str::to_string).collect();
+
+    let castles: String = CastleKind::all()
+        .zip("KQkq".chars())
+        .filter(|&(castle, c)| {
+            let color = if c.is_uppercase() { Color::White } else { Color::Black };
+            let king = Square::E1.relative(color);
+
+            fields[2].contains(c) &&
+            board.state.at(king) == Some(Piece::new(PieceKind::King, color)) &&
+            board.state.at(castle.rook_from()) == Some(Piece::new(PieceKind::Rook, color))
+        })
+        .map(|(_, c)| c)
+        .collect();
+
+    fields[2] = if castles.is_empty() { "-".to_string() } else { castles };
+
+    if let Some(enpassant) = board.state.enpassant() {
+        let pawn = Piece::new(PieceKind::Pawn, !board.color);
+
+        if board.state.at(enpassant).is_some() || board.state.at(enpassant.enpassant()) != Some(pawn) {
+            fields[3] = "-".to_string();
+        }
+    }
+
+    fields.join(" ")
+}
+
+fn candidates(fen: &str) -> Vec<String> {
+    let board = Board::from_fen(fen).unwrap();
+    let mut candidates = Vec::new();
+
+    for square in Square::all() {
+        if board.state.at(square).is_some_and(|piece| piece.kind() != PieceKind::King) {
+            let mut candidate = board.clone();
+
+            candidate.state.remove(square);
+            candidates.push(sanitize(&candidate));
+        }
+    }
+
+    let fields: Vec<&str> = fen.split(' ').collect();
+
+    if fields[2] != "-" {
+        candidates.push([fields[0], fields[1], "-", fields[3], fields[4], fields[5]].join(" "));
+    }
+
+    if fields[3] != "-" {
+        candidates.push([fields[0], fields[1], fields[2], "-", fields[4], fields[5]].join(" "));
+    }
+
+    candidates
+}
+
+// Greedily removes pieces, castling rights and the enpassant square while the position keeps failing
+pub fn shrink(fen: &str, fails: impl Fn(&mut Board) -> bool) -> String {
+    let mut current = fen.to_string();
+
+    loop {
+        let next = candidates(&current).into_iter().find(|candidate| {
+            let mut board = Board::from_fen(candidate).unwrap();
+
+            is_sane(&board) && fails(&mut board)
+        });
+
+        match next {
+            Some(next) => current = next,
+            None => return current
+        }
+    }
+}
+
+// Plays random games from a few well known positions and checks every position along the way
+pub fn fuzz(seed: u64, games: usize, plies: usize) -> Result<usize, Discrepancy> {
+    let mut random = Random::new(seed);
+    let mut positions = 0;
+
+    for game in 0..games {
+        let mut board = Board::from_fen(START_FENS[game % START_FENS.len()]).unwrap();
+
+        for _ in 0..plies {
+            if let Err(message) = check(&mut board) {
+                let fen = shrink(&board.to_fen(), |board| check(board).is_err());
+                let message = check(&mut Board::from_fen(&fen).unwrap()).err().unwrap_or(message);
+
+                return Err(Discrepancy { fen, message });
+            }
+
+            positions += 1;
+
+            let moves: Vec<Move> = board.generate_moves().iter_moves().copied().filter(|&mv| board.is_legal(mv)).collect();
+
+            if moves.is_empty() || board.state.halfmove() >= 100 {
+                break;
+            }
+
+            board.make(moves[random.below(moves.len())]);
+        }
+    }
+
+    Ok(positions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn random_games() {
+        if let Err(discrepancy) = fuzz(0x7ea, 10, 200) {
+            panic!("{}", discrepancy);
+        }
+    }
+
+    #[test]
+    fn shrink_to_minimal() {
+        let fen = shrink(
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            |board| (board.state.pieces(PieceKind::Queen) & board.state.colors(Color::White)).is_some()
+        );
+
+        assert_eq!(fen, "4k3/8/8/8/8/5Q2/8/4K3 w - - 0 1");
+    }
+}
\ No newline at end of file
@@ -1,18 +1,28 @@
-include!(concat!(env!("OUT_DIR"), "/magic.rs"));
-
-use crate::chess::bitboard::Bitboard;
-
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
-pub struct Magic {
-    pub mask: u64,
-    pub magic: u64,
-    pub shift: u32,
-    pub offset: usize
-}
-
-impl Magic {
-    #[inline(always)]
-    pub const fn index(self, occupied: Bitboard) -> usize {
-        ((occupied.value() & self.mask).wrapping_mul(self.magic) >> self.shift) as usize + self.offset
-    }
+include!(concat!(env!("OUT_DIR"), "/magic.rs"));
+
+use crate::chess::bitboard::Bitboard;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Magic {
+    pub mask: u64,
+    pub magic: u64,
+    pub shift: u32,
+    pub offset: usize
+}
+
+#[cfg(all(feature = "pext", not(target_feature = "bmi2")))]
+compile_error!("the pext feature needs bmi2, build with -C target-cpu=native or -C target-feature=+bmi2");
+
+impl Magic {
+    #[cfg(not(feature = "pext"))]
+    #[inline(always)]
+    pub const fn index(self, occupied: Bitboard) -> usize {
+        ((occupied.value() & self.mask).wrapping_mul(self.magic) >> self.shift) as usize + self.offset
+    }
+
+    #[cfg(feature = "pext")]
+    #[inline(always)]
+    pub fn index(self, occupied: Bitboard) -> usize {
+        unsafe { std::arch::x86_64::_pext_u64(occupied.value(), self.mask) as usize + self.offset }
+    }
 }
\ No newline at end of file
@@ -1,15 +1,23 @@
-pub mod color;
-pub mod piece;
-pub mod file;
-pub mod rank;
-pub mod square;
-pub mod castle;
-pub mod moves;
-pub mod direction;
-pub mod bitboard;
-pub mod magic;
-pub mod attack;
-pub mod zobrist;
-pub mod board;
-pub mod movelist;
-pub mod movegen;
\ No newline at end of file
+pub mod color;
+pub mod result;
+pub mod piece;
+pub mod file;
+pub mod rank;
+pub mod square;
+pub mod castle;
+pub mod moves;
+pub mod direction;
+pub mod bitboard;
+pub mod magic;
+pub mod attack;
+pub mod zobrist;
+pub mod polyglot;
+pub mod pgn;
+pub mod board;
+pub mod packed;
+pub mod movelist;
+pub mod movegen;
+pub mod perft;
+pub mod random;
+pub mod reference;
+pub mod fuzz;
\ No newline at end of file
@@ -0,0 +1,364 @@
+use std::io::{Read, Write};
+
+use crate::chess::{bitboard::Bitboard, board::{Board, FenParseError}, castle::CastleKind, color::Color, piece::{Piece, PieceKind}, result::GameResult, square::Square};
+
+// Piece code of a rook that can still castle
+const UNMOVED_ROOK: u8 = 6;
+
+// A position with its score and result in 32 bytes, laid out like marlinformat, little endian:
+// occupancy, a nibble per occupied square in square order, side to move and enpassant, halfmove, fullmove, score, wdl and a spare byte
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct PackedBoard {
+    occupied: u64,
+    pieces: [u8; 16],
+    color_enpassant: u8,
+    halfmove: u8,
+    fullmove: u16,
+    score: i16,
+    wdl: u8,
+    extra: u8
+}
+
+// Skips records that don't describe a board instead of stopping at them
+pub struct PackedReader<R: Read> {
+    reader: R,
+    skipped: usize
+}
+
+pub struct PackedWriter<W: Write> {
+    writer: W
+}
+
+impl PackedBoard {
+    pub const SIZE: usize = 32;
+
+    // The score is from white's point of view, None for boards with more than the 32 pieces a record has room for
+    pub fn new(board: &Board, score: i32, result: GameResult) -> Option<Self> {
+        let state = &board.state;
+        let mut pieces = [0; 16];
+
+        if state.occupied().count() > 32 {
+            return None;
+        }
+
+        for (i, square) in state.occupied().enumerate() {
+            let piece = state.at(square).unwrap();
+            let castling = CastleKind::from_corner(square).is_some_and(|kind| state.castles().is_allowed(kind) && piece == Piece::new(PieceKind::Rook, kind.color()));
+            let code = if castling { UNMOVED_ROOK } else { piece.kind().value() } | piece.color().value() << 3;
+
+            pieces[i / 2] |= code << (4 * (i % 2));
+        }
+
+        Some(Self {
+            occupied: state.occupied().value(),
+            pieces,
+            color_enpassant: board.color.value() << 7 | state.enpassant().map_or(64, Square::value),
+            halfmove: state.halfmove(),
+            fullmove: state.fullmove(),
+            score: score.clamp(i16::MIN as i32, i16::MAX as i32) as i16,
+            wdl: match result {
+                GameResult::WhiteWin => 2,
+                GameResult::BlackWin => 0,
+                _ => 1
+            },
+            extra: 0
+        })
+    }
+
+    pub fn from_fen(fen: &str, score: i32, result: GameResult) -> Option<Self> {
+        Self::new(&Board::from_fen(fen).ok()?, score, result)
+    }
+
+    pub fn score(&self) -> i32 {
+        self.score as i32
+    }
+
+    pub fn result(&self) -> GameResult {
+        match self.wdl {
+            2 => GameResult::WhiteWin,
+            0 => GameResult::BlackWin,
+            _ => GameResult::Draw
+        }
+    }
+
+    // Occupied squares with their piece codes, in square order
+    fn squares(&self) -> impl Iterator<Item = (Square, u8)> + '_ {
+        Bitboard::from_raw(self.occupied).into_iter().enumerate().map(|(i, square)| (square, self.pieces[i / 2] >> (4 * (i % 2)) & 0xf))
+    }
+
+    pub fn to_fen(self) -> String {
+        let mut mailbox = [None; Square::COUNT];
+        let mut castles = String::new();
+
+        for (square, code) in self.squares() {
+            let color = Color::from_raw(code >> 3 == 1);
+            let kind = code & 7;
+
+            mailbox[square.index()] = Some(Piece::new(PieceKind::from_raw(if kind == UNMOVED_ROOK { PieceKind::Rook.value() } else { kind }), color));
+        }
+
+        for (kind, c) in CastleKind::all().zip("KQkq".chars()) {
+            let code = self.squares().find(|&(square, _)| square == kind.rook_from()).map(|(_, code)| code);
+
+            if code == Some(UNMOVED_ROOK | kind.color().value() << 3) {
+                castles.push(c);
+            }
+        }
+
+        let rows: Vec<String> = (0..8).rev().map(|rank| {
+            let mut row = String::new();
+            let mut empty = 0;
+
+            for piece in &mailbox[rank * 8..rank * 8 + 8] {
+                match piece {
+                    Some(piece) => {
+                        if empty > 0 {
+                            row += &empty.to_string();
+                            empty = 0;
+                        }
+
+                        row += &piece.to_string();
+                    },
+                    None => empty += 1
+                }
+            }
+
+            if empty > 0 {
+                row += &empty.to_string();
+            }
+
+            row
+        }).collect();
+
+        let color = Color::from_raw(self.color_enpassant >> 7 == 1);
+        let enpassant = match self.color_enpassant & 0x7f {
+            64 => "-".to_string(),
+            square => Square::from_raw(square).to_string()
+        };
+
+        format!("{} {} {} {} {} {}", rows.join("/"), color, if castles.is_empty() { "-" } else { &castles }, enpassant, self.halfmove, self.fullmove)
+    }
+
+    pub fn to_board(self) -> Result<Board, FenParseError> {
+        Board::from_fen(&self.to_fen())
+    }
+
+    // Reads the "fen | score | result" lines written by datagen, the result is 1.0, 0.5 or 0.0 for white
+    pub fn from_line(line: &str) -> Option<Self> {
+        let mut fields = line.split('|').map(str::trim);
+        let (fen, score, result) = (fields.next()?, fields.next()?, fields.next()?);
+
+        let result = match result {
+            "1.0" | "1-0" => GameResult::WhiteWin,
+            "0.0" | "0-1" => GameResult::BlackWin,
+            "0.5" | "1/2-1/2" => GameResult::Draw,
+            _ => return None
+        };
+
+        Self::from_fen(fen, score.parse().ok()?, result)
+    }
+
+    pub fn to_line(self) -> String {
+        let result = match self.result() {
+            GameResult::WhiteWin => "1.0",
+            GameResult::BlackWin => "0.0",
+            _ => "0.5"
+        };
+
+        format!("{} | {} | {}", self.to_fen(), self.score, result)
+    }
+
+    // None for more than 32 pieces, an unknown piece code, an enpassant square off the board or an unknown wdl
+    pub fn from_bytes(bytes: &[u8; Self::SIZE]) -> Option<Self> {
+        let packed = Self {
+            occupied: u64::from_le_bytes(bytes[0..8].try_into().unwrap()),
+            pieces: bytes[8..24].try_into().unwrap(),
+            color_enpassant: bytes[24],
+            halfmove: bytes[25],
+            fullmove: u16::from_le_bytes(bytes[26..28].try_into().unwrap()),
+            score: i16::from_le_bytes(bytes[28..30].try_into().unwrap()),
+            wdl: bytes[30],
+            extra: bytes[31]
+        };
+
+        let valid = packed.occupied.count_ones() <= 32
+            && packed.squares().all(|(_, code)| code & 7 <= UNMOVED_ROOK)
+            && packed.color_enpassant & 0x7f <= 64
+            && packed.wdl <= 2;
+
+        valid.then_some(packed)
+    }
+
+    pub fn to_bytes(self) -> [u8; Self::SIZE] {
+        let mut bytes = [0; Self::SIZE];
+
+        bytes[0..8].copy_from_slice(&self.occupied.to_le_bytes());
+        bytes[8..24].copy_from_slice(&self.pieces);
+        bytes[24] = self.color_enpassant;
+        bytes[25] = self.halfmove;
+        bytes[26..28].copy_from_slice(&self.fullmove.to_le_bytes());
+        bytes[28..30].copy_from_slice(&self.score.to_le_bytes());
+        bytes[30] = self.wdl;
+        bytes[31] = self.extra;
+
+        bytes
+    }
+
+    // The bulletformat record, seen from the side to move: the board is flipped for black and the pieces of the side to move get color 0,
+    // then score, wdl, the king square of the side to move, the other king square flipped, and three spare bytes
+    pub fn to_bullet(self) -> [u8; Self::SIZE] {
+        let black = self.color_enpassant >> 7 == 1;
+        let relative = |square: Square| if black { square.flip_rank() } else { square };
+
+        let mut squares: Vec<(Square, u8)> = self.squares().map(|(square, code)| {
+            let kind = if code & 7 == UNMOVED_ROOK { PieceKind::Rook.value() } else { code & 7 };
+
+            (relative(square), kind | ((code >> 3) ^ black as u8) << 3)
+        }).collect();
+
+        squares.sort_by_key(|&(square, _)| square.index());
+
+        let king = |color: u8| squares.iter().find(|&&(_, code)| code == PieceKind::King.value() | color << 3).map_or(0, |&(square, _)| square.value());
+
+        let mut bytes = [0; Self::SIZE];
+        let occupied = if black { self.occupied.swap_bytes() } else { self.occupied };
+
+        bytes[0..8].copy_from_slice(&occupied.to_le_bytes());
+
+        for (i, &(_, code)) in squares.iter().enumerate() {
+            bytes[8 + i / 2] |= code << (4 * (i % 2));
+        }
+
+        let score = if black { self.score.saturating_neg() } else { self.score };
+
+        bytes[24..26].copy_from_slice(&score.to_le_bytes());
+        bytes[26] = if black { 2 - self.wdl } else { self.wdl };
+        bytes[27] = king(0);
+        bytes[28] = king(1) ^ 56;
+
+        bytes
+    }
+}
+
+impl<R: Read> PackedReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self { reader, skipped: 0 }
+    }
+
+    pub fn skipped(&self) -> usize {
+        self.skipped
+    }
+}
+
+// Stops at the end of the stream or at a truncated record
+impl<R: Read> Iterator for PackedReader<R> {
+    type Item = PackedBoard;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut bytes = [0; PackedBoard::SIZE];
+
+        loop {
+            self.reader.read_exact(&mut bytes).ok()?;
+
+            match PackedBoard::from_bytes(&bytes) {
+                Some(packed) => return Some(packed),
+                None => self.skipped += 1
+            }
+        }
+    }
+}
+
+impl<W: Write> PackedWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    pub fn write(&mut self, board: &PackedBoard) -> std::io::Result<()> {
+        self.writer.write_all(&board.to_bytes())
+    }
+
+    pub fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layout() {
+        let board = Board::startpos().unwrap();
+        let bytes = PackedBoard::new(&board, -25, GameResult::BlackWin).unwrap().to_bytes();
+
+        assert_eq!(u64::from_le_bytes(bytes[0..8].try_into().unwrap()), 0xffff00000000ffff);
+        assert_eq!(&bytes[8..12], &[0x16, 0x42, 0x25, 0x61]);
+        assert_eq!(bytes[20..24], [0x9e, 0xca, 0xad, 0xe9]);
+        assert_eq!((bytes[24], bytes[25], &bytes[26..28], &bytes[28..30], bytes[30]), (64, 0, &[1, 0][..], &(-25i16).to_le_bytes()[..], 0));
+
+        // Seen from black the start position looks the same with the score and result flipped
+        let black = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b KQkq - 0 1").unwrap();
+        let bullet = PackedBoard::new(&black, -25, GameResult::BlackWin).unwrap().to_bullet();
+
+        assert_eq!(&bullet[0..24], &PackedBoard::new(&board, 0, GameResult::Draw).unwrap().to_bullet()[0..24]);
+        assert_eq!((&bullet[24..26], bullet[26], bullet[27], bullet[28]), (&25i16.to_le_bytes()[..], 2, Square::E1.value(), Square::E1.value()));
+    }
+
+    #[test]
+    fn round_trip() {
+        let fens = [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w Kq - 3 17",
+            "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 b - - 49 120"
+        ];
+
+        let mut bytes = Vec::new();
+        let mut writer = PackedWriter::new(&mut bytes);
+
+        for (i, fen) in fens.iter().enumerate() {
+            let packed = PackedBoard::from_fen(fen, i as i32 * 100 - 150, GameResult::Draw).unwrap();
+
+            assert_eq!(packed.to_fen(), *fen);
+            assert_eq!(packed.to_board().unwrap().state.key(), Board::from_fen(fen).unwrap().state.key());
+            assert_eq!(PackedBoard::from_line(&packed.to_line()), Some(packed));
+
+            writer.write(&packed).unwrap();
+        }
+
+        assert_eq!(bytes.len(), fens.len() * PackedBoard::SIZE);
+
+        let read: Vec<PackedBoard> = PackedReader::new(&bytes[..bytes.len() - 1]).collect();
+
+        assert_eq!(read.len(), fens.len() - 1);
+        assert!(read.iter().zip(fens).all(|(packed, fen)| packed.to_fen() == fen));
+    }
+
+    #[test]
+    fn invalid() {
+        let valid = PackedBoard::new(&Board::startpos().unwrap(), 0, GameResult::Draw).unwrap().to_bytes();
+        let corrupt = |index: usize, value: u8| {
+            let mut bytes = valid;
+
+            bytes[index] = value;
+            bytes
+        };
+
+        let records = [corrupt(4, 0xff), corrupt(8, 0x17), corrupt(24, 65), corrupt(24, 0xff), corrupt(30, 3)];
+
+        assert!(PackedBoard::from_bytes(&valid).is_some());
+        assert!(records.iter().all(|bytes| PackedBoard::from_bytes(bytes).is_none()));
+
+        let stream: Vec<u8> = [valid, records[0], records[2], valid].concat();
+        let mut reader = PackedReader::new(stream.as_slice());
+
+        assert_eq!(reader.by_ref().count(), 2);
+        assert_eq!(reader.skipped(), 2);
+
+        // A legal board can still hold more pieces than a record has room for
+        let crowded = "rnbqkbnr/pppppppp/8/2NN4/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+        assert!(Board::from_fen(crowded).is_ok());
+        assert_eq!(PackedBoard::from_fen(crowded, 0, GameResult::Draw), None);
+    }
+}
\ No newline at end of file
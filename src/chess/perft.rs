@@ -0,0 +1,294 @@
+use std::{sync::atomic::{AtomicU64, AtomicUsize, Ordering}, thread};
+
+use crate::chess::{board::{Board, FenParseError, Key}, moves::Move};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EpdParseError {
+    MissingFen,
+    InvalidFen(FenParseError),
+    InvalidDepth
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PerftCase {
+    pub fen: String,
+    pub depths: Vec<(i32, usize)>
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PerftMismatch {
+    pub fen: String,
+    pub depth: i32,
+    pub expected: usize,
+    pub found: usize
+}
+
+#[derive(Debug, Default)]
+struct PerftEntry {
+    key: AtomicU64,
+    data: AtomicU64
+}
+
+#[derive(Debug)]
+pub struct PerftTable {
+    entries: Vec<PerftEntry>
+}
+
+#[derive(Debug)]
+pub struct Perft {
+    table: PerftTable,
+    threads: usize
+}
+
+impl PerftTable {
+    const DEPTH_BITS: u32 = 8;
+    const DEPTH_MASK: u64 = (1 << Self::DEPTH_BITS) - 1;
+
+    pub fn new(mb: usize) -> Self {
+        let count = mb * 1024 * 1024 / std::mem::size_of::<PerftEntry>();
+
+        Self { entries: (0..count).map(|_| PerftEntry::default()).collect() }
+    }
+
+    #[inline(always)]
+    fn index(&self, key: Key, depth: i32) -> usize {
+        let hash = key.main ^ (depth as u64).wrapping_mul(0x9e3779b97f4a7c15);
+
+        ((hash as u128 * self.entries.len() as u128) >> 64) as usize
+    }
+
+    // The stored key is xored with the data so that torn writes from other threads fail verification
+    pub fn probe(&self, key: Key, depth: i32) -> Option<usize> {
+        if self.entries.is_empty() {
+            return None;
+        }
+
+        let entry = &self.entries[self.index(key, depth)];
+        let data = entry.data.load(Ordering::Relaxed);
+
+        if entry.key.load(Ordering::Relaxed) ^ data != key.main || data & Self::DEPTH_MASK != depth as u64 {
+            return None;
+        }
+
+        Some((data >> Self::DEPTH_BITS) as usize)
+    }
+
+    pub fn store(&self, key: Key, depth: i32, nodes: usize) {
+        if self.entries.is_empty() {
+            return;
+        }
+
+        let entry = &self.entries[self.index(key, depth)];
+        let data = (nodes as u64) << Self::DEPTH_BITS | depth as u64;
+
+        entry.key.store(key.main ^ data, Ordering::Relaxed);
+        entry.data.store(data, Ordering::Relaxed);
+    }
+}
+
+impl PerftCase {
+    // Parses a perftsuite line such as "<fen> ;D1 20 ;D2 400"
+    pub fn from_epd(line: &str) -> Result<Self, EpdParseError> {
+        let mut fields = line.split(';');
+
+        let fen = fields.next().map(str::trim).filter(|fen| !fen.is_empty()).ok_or(EpdParseError::MissingFen)?;
+
+        Board::from_fen(fen).map_err(EpdParseError::InvalidFen)?;
+
+        let mut depths = Vec::new();
+
+        for field in fields {
+            let mut parts = field.split_whitespace();
+
+            let depth = parts
+                .next()
+                .and_then(|depth| depth.strip_prefix('D'))
+                .and_then(|depth| depth.parse().ok())
+                .ok_or(EpdParseError::InvalidDepth)?;
+
+            let nodes = parts
+                .next()
+                .and_then(|nodes| nodes.parse().ok())
+                .ok_or(EpdParseError::InvalidDepth)?;
+
+            depths.push((depth, nodes));
+        }
+
+        Ok(Self { fen: fen.to_string(), depths })
+    }
+}
+
+impl std::fmt::Display for PerftMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ;D{} expected {} found {}", self.fen, self.depth, self.expected, self.found)
+    }
+}
+
+impl Perft {
+    pub fn new(mb: usize, threads: usize) -> Self {
+        Self {
+            table: PerftTable::new(mb),
+            threads: threads.max(1)
+        }
+    }
+
+    pub fn run<const ROOT: bool>(&self, board: &Board, depth: i32) -> usize {
+        let moves: Vec<Move> = board
+            .generate_moves()
+            .iter_moves()
+            .copied()
+            .filter(|&mv| board.is_legal(mv))
+            .collect();
+
+        let next = AtomicUsize::new(0);
+        let mut counts = vec![0; moves.len()];
+
+        // Root moves are handed out one by one so that the workers stay busy until the end
+        thread::scope(|scope| {
+            let workers: Vec<_> = (0..self.threads.min(moves.len()))
+                .map(|_| {
+                    // Boards cache attack maps in cells, so each worker gets its own copy instead of sharing one
+                    let mut board = board.clone();
+                    let (next, moves) = (&next, &moves);
+
+                    scope.spawn(move || {
+                        let mut results = Vec::new();
+
+                        loop {
+                            let index = next.fetch_add(1, Ordering::Relaxed);
+
+                            if index >= moves.len() {
+                                break;
+                            }
+
+                            let nodes = if depth > 1 {
+                                board.make(moves[index]);
+                                let nodes = self.count(&mut board, depth - 1);
+                                board.unmake();
+
+                                nodes
+                            }
+                            else {
+                                1
+                            };
+
+                            results.push((index, nodes));
+                        }
+
+                        results
+                    })
+                })
+                .collect();
+
+            for worker in workers {
+                for (index, nodes) in worker.join().unwrap() {
+                    counts[index] = nodes;
+                }
+            }
+        });
+
+        if ROOT {
+            for (mv, nodes) in moves.iter().zip(counts.iter()) {
+                println!("{} - {}", mv, nodes);
+            }
+        }
+
+        counts.iter().sum()
+    }
+
+    // Checks every depth of the case up to max depth, printing the divide of the first mismatch
+    pub fn check(&self, case: &PerftCase, max_depth: i32) -> Result<(), PerftMismatch> {
+        let board = Board::from_fen(&case.fen).unwrap();
+
+        for &(depth, expected) in case.depths.iter().filter(|(depth, _)| *depth <= max_depth) {
+            let found = self.run::<false>(&board, depth);
+
+            if found != expected {
+                self.run::<true>(&board, depth);
+
+                return Err(PerftMismatch { fen: case.fen.clone(), depth, expected, found });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn count(&self, board: &mut Board, depth: i32) -> usize {
+        let moves = board.generate_moves();
+
+        // Bulk counting, legal moves at the last ply are leaves
+        if depth <= 1 {
+            return moves.iter_moves().filter(|&&mv| board.is_legal(mv)).count();
+        }
+
+        let key = board.state.key();
+
+        if let Some(nodes) = self.table.probe(key, depth) {
+            return nodes;
+        }
+
+        let mut count = 0;
+
+        for &mv in moves.iter_moves() {
+            if !board.is_legal(mv) {
+                continue;
+            }
+
+            board.make(mv);
+            count += self.count(board, depth - 1);
+            board.unmake();
+        }
+
+        self.table.store(key, depth, count);
+
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chess::movegen::perft;
+
+    const POSITIONS: [(&str, i32, usize); 6] = [
+        ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", 6, 119060324),
+        ("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1", 5, 193690690),
+        ("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1", 7, 178633661),
+        ("r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1", 5, 15833292),
+        ("rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8", 5, 89941194),
+        ("r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10", 5, 164075551)
+    ];
+
+    #[test]
+    fn hashed() {
+        let perft = Perft::new(16, 4);
+
+        for (fen, depth, nodes) in POSITIONS {
+            let board = Board::from_fen(fen).unwrap();
+            assert_eq!(perft.run::<false>(&board, depth), nodes, "{}", fen);
+        }
+    }
+
+    #[test]
+    fn suite() {
+        let perft = Perft::new(16, 4);
+
+        for line in include_str!("../../res/perftsuite.epd").lines() {
+            let case = PerftCase::from_epd(line).unwrap();
+
+            if let Err(mismatch) = perft.check(&case, 4) {
+                panic!("{}", mismatch);
+            }
+        }
+    }
+
+    #[test]
+    fn unhashed() {
+        let engine = Perft::new(0, 1);
+
+        for (fen, depth, _) in POSITIONS {
+            let mut board = Board::from_fen(fen).unwrap();
+            assert_eq!(engine.run::<false>(&board, depth - 2), perft::<false>(&mut board, depth - 2), "{}", fen);
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,294 @@
+use std::io::BufRead;
+
+use crate::chess::{board::Board, file::File, moves::Move, piece::{Piece, PieceKind}, rank::Rank, result::GameResult, square::Square};
+
+#[derive(Debug, Clone, Default)]
+pub struct PgnGame {
+    pub tags: Vec<(String, String)>,
+    pub moves: Vec<String>,
+    pub result: Option<GameResult>
+}
+
+pub struct PgnReader<R: BufRead> {
+    reader: R,
+    line: String
+}
+
+impl PgnGame {
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags.iter().find(|(tag, _)| tag == name).map(|(_, value)| value.as_str())
+    }
+
+    // The result token after the moves wins over the tag, both are usually the same
+    pub fn result(&self) -> GameResult {
+        self.result
+            .or_else(|| self.tag("Result").and_then(GameResult::from_str))
+            .unwrap_or(GameResult::Unknown)
+    }
+
+    pub fn start(&self) -> Option<Board> {
+        match self.tag("FEN") {
+            Some(fen) => Board::from_fen(fen).ok(),
+            None => Board::startpos().ok()
+        }
+    }
+
+    // Appends the main line of a chunk of movetext, skipping comments, variations, NAGs and move numbers
+    fn parse_movetext(&mut self, text: &str, comment: &mut bool, variation: &mut usize) {
+        let mut token = String::new();
+        let mut chars = text.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            if *comment {
+                *comment = c != '}';
+                continue;
+            }
+
+            match c {
+                '{' => *comment = true,
+                ';' => break,
+                '(' => *variation += 1,
+                ')' => *variation = variation.saturating_sub(1),
+                _ if c.is_whitespace() => {},
+                _ => {
+                    token.push(c);
+
+                    if chars.peek().is_some_and(|&next| !next.is_whitespace() && !"{;()".contains(next)) {
+                        continue;
+                    }
+                }
+            }
+
+            if !token.is_empty() {
+                if *variation == 0 {
+                    self.push_token(&token);
+                }
+
+                token.clear();
+            }
+        }
+    }
+
+    fn push_token(&mut self, token: &str) {
+        if let Some(result) = GameResult::from_str(token) {
+            self.result = Some(result);
+            return;
+        }
+
+        // Strip move numbers, which may be glued to the move as in "1.e4"
+        let token = token.trim_start_matches(|c: char| c.is_ascii_digit()).trim_start_matches('.');
+
+        if token.is_empty() || token.starts_with('$') {
+            return;
+        }
+
+        self.moves.push(token.to_string());
+    }
+}
+
+impl<R: BufRead> PgnReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            line: String::new()
+        }
+    }
+
+    fn parse_tag(line: &str) -> Option<(String, String)> {
+        let line = line.trim().strip_prefix('[')?.strip_suffix(']')?;
+        let (name, value) = line.split_once(' ')?;
+        let value = value.trim().strip_prefix('"')?.strip_suffix('"')?;
+
+        Some((name.to_string(), value.replace("\\\"", "\"")))
+    }
+}
+
+impl<R: BufRead> Iterator for PgnReader<R> {
+    type Item = PgnGame;
+
+    fn next(&mut self) -> Option<PgnGame> {
+        let mut game = PgnGame::default();
+        let mut comment = false;
+        let mut variation = 0;
+        let mut movetext = false;
+
+        loop {
+            if self.line.is_empty() && self.reader.read_line(&mut self.line).unwrap_or(0) == 0 {
+                break;
+            }
+
+            let text = std::mem::take(&mut self.line);
+            let line = text.trim();
+
+            if !comment && line.starts_with('[') {
+                // A tag after movetext starts the next game, keep the line for the next call
+                if movetext {
+                    self.line = text;
+                    break;
+                }
+
+                if let Some(tag) = Self::parse_tag(line) {
+                    game.tags.push(tag);
+                }
+
+                continue;
+            }
+
+            if line.is_empty() || line.starts_with('%') {
+                continue;
+            }
+
+            movetext = true;
+
+            game.parse_movetext(line, &mut comment, &mut variation);
+
+            if game.result.is_some() && !comment && variation == 0 {
+                break;
+            }
+        }
+
+        match game.tags.is_empty() && game.moves.is_empty() {
+            true => None,
+            false => Some(game)
+        }
+    }
+}
+
+// Parses standard algebraic notation such as "Nbd7", "exd8=Q+" or "O-O" into a legal move
+pub fn parse_san(board: &Board, san: &str) -> Option<Move> {
+    let san = san.trim_end_matches(['+', '#', '!', '?']);
+    let legal = board.generate_moves().iter_moves().copied().filter(|&mv| board.is_legal(mv)).collect::<Vec<_>>();
+
+    let castle = match san {
+        "O-O" | "0-0" => Some(true),
+        "O-O-O" | "0-0-0" => Some(false),
+        _ => None
+    };
+
+    if let Some(short) = castle {
+        return legal.into_iter().find(|&mv| mv.is_castling() && (mv.to() > mv.from()) == short);
+    }
+
+    let (san, promotion) = match san.split_once('=') {
+        Some((san, promotion)) => (san, Some(Piece::from_char(promotion.chars().next()?)?.kind())),
+        None => match san.chars().last().and_then(Piece::from_char) {
+            Some(piece) if san.len() > 2 && piece.kind() != PieceKind::Pawn && piece.kind() != PieceKind::King && san.as_bytes()[0].is_ascii_lowercase() => (&san[..san.len() - 1], Some(piece.kind())),
+            _ => (san, None)
+        }
+    };
+
+    let (kind, san) = match san.chars().next().filter(char::is_ascii_uppercase) {
+        Some(c) => (Piece::from_char(c)?.kind(), &san[1..]),
+        None => (PieceKind::Pawn, san)
+    };
+
+    if san.len() < 2 {
+        return None;
+    }
+
+    let to = Square::from_str(&san[san.len() - 2..])?;
+    let hint = san[..san.len() - 2].trim_end_matches('x');
+
+    let mut from_file = None;
+    let mut from_rank = None;
+
+    for c in hint.chars() {
+        match (File::from_char(c), Rank::from_char(c)) {
+            (Some(file), _) => from_file = Some(file),
+            (_, Some(rank)) => from_rank = Some(rank),
+            _ => return None
+        }
+    }
+
+    let mut candidates = legal.into_iter().filter(|&mv| {
+        mv.to() == to &&
+        !mv.is_castling() &&
+        mv.promotion_kind() == promotion &&
+        board.state.at(mv.from()).is_some_and(|piece| piece.kind() == kind) &&
+        from_file.is_none_or(|file| mv.from().file() == file) &&
+        from_rank.is_none_or(|rank| mv.from().rank() == rank)
+    });
+
+    let mv = candidates.next()?;
+
+    match candidates.next() {
+        Some(_) => None,
+        None => Some(mv)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chess::moves::MoveKind;
+
+    #[test]
+    fn san() {
+        let board = Board::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1").unwrap();
+
+        assert_eq!(parse_san(&board, "O-O"), Some(Move::new(Square::E1, Square::G1, MoveKind::Castling)));
+        assert_eq!(parse_san(&board, "O-O-O+"), Some(Move::new(Square::E1, Square::C1, MoveKind::Castling)));
+        assert_eq!(parse_san(&board, "Nxf7"), Some(Move::new(Square::E5, Square::F7, MoveKind::Normal)));
+        assert_eq!(parse_san(&board, "dxe6"), Some(Move::new(Square::D5, Square::E6, MoveKind::Normal)));
+        assert_eq!(parse_san(&board, "Qxh3!?"), Some(Move::new(Square::F3, Square::H3, MoveKind::Normal)));
+        assert_eq!(parse_san(&board, "Nb1"), Some(Move::new(Square::C3, Square::B1, MoveKind::Normal)));
+        assert_eq!(parse_san(&board, "Rb1"), Some(Move::new(Square::A1, Square::B1, MoveKind::Normal)));
+        assert_eq!(parse_san(&board, "Nd1"), Some(Move::new(Square::C3, Square::D1, MoveKind::Normal)));
+        assert_eq!(parse_san(&board, "Ng4"), Some(Move::new(Square::E5, Square::G4, MoveKind::Normal)));
+        assert_eq!(parse_san(&board, "Nxe6"), None);
+        assert_eq!(parse_san(&board, "e5"), None);
+
+        let board = Board::from_fen("4k3/1P6/8/8/8/8/4K3/R6R w - - 0 1").unwrap();
+
+        assert_eq!(parse_san(&board, "b8=N"), Some(Move::new(Square::B7, Square::B8, MoveKind::PromotionKnight)));
+        assert_eq!(parse_san(&board, "b8Q+"), Some(Move::new(Square::B7, Square::B8, MoveKind::PromotionQueen)));
+        assert_eq!(parse_san(&board, "b8"), None);
+        assert_eq!(parse_san(&board, "Rad1"), Some(Move::new(Square::A1, Square::D1, MoveKind::Normal)));
+        assert_eq!(parse_san(&board, "Rhd1"), Some(Move::new(Square::H1, Square::D1, MoveKind::Normal)));
+        assert_eq!(parse_san(&board, "Rd1"), None);
+
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/R3K2R w - - 0 1").unwrap();
+
+        assert_eq!(parse_san(&board, "O-O"), None);
+        assert_eq!(parse_san(&board, "R1a7"), Some(Move::new(Square::A1, Square::A7, MoveKind::Normal)));
+    }
+
+    #[test]
+    fn reader() {
+        let pgn = "\
+[Event \"First\"]
+[Result \"1-0\"]
+
+1. e4 {a comment (with parens)} e5 2.Nf3 $1 (2. Nc3 {side line} Nc6 (2... Nf6)) Nc6 ; rest of line
+3. Bb5 a6 1-0
+
+[Event \"Second\"]
+[FEN \"4k3/8/8/8/8/8/8/R3K2R w K - 0 30\"]
+[Result \"*\"]
+
+30. O-O Kd7 {a
+comment over two lines} 31. Rf7+ 1/2-1/2
+";
+
+        let games: Vec<PgnGame> = PgnReader::new(pgn.as_bytes()).collect();
+
+        assert_eq!(games.len(), 2);
+        assert_eq!(games[0].tag("Event"), Some("First"));
+        assert_eq!(games[0].moves, ["e4", "e5", "Nf3", "Nc6", "Bb5", "a6"]);
+        assert_eq!(games[0].result(), GameResult::WhiteWin);
+        assert_eq!(games[1].moves, ["O-O", "Kd7", "Rf7+"]);
+        assert_eq!(games[1].result(), GameResult::Draw);
+
+        let mut board = games[1].start().unwrap();
+
+        assert_eq!(board.state.fullmove(), 30);
+
+        for san in games[1].moves.iter() {
+            let mv = parse_san(&board, san).unwrap();
+
+            board.make(mv);
+        }
+
+        assert_eq!(board.to_fen(), "8/3k1R2/8/8/8/8/8/R5K1 b - - 3 31");
+    }
+}
\ No newline at end of file
@@ -0,0 +1,24 @@
+#[derive(Debug, Clone)]
+pub struct Random {
+    seed: u64
+}
+
+impl Random {
+    pub const fn new(seed: u64) -> Self {
+        Self { seed: if seed == 0 { 0x9e3779b97f4a7c15 } else { seed } }
+    }
+
+    #[inline(always)]
+    pub fn next_u64(&mut self) -> u64 {
+        self.seed ^= self.seed >> 12;
+        self.seed ^= self.seed << 25;
+        self.seed ^= self.seed >> 27;
+
+        self.seed.wrapping_mul(2685821657736338717)
+    }
+
+    #[inline(always)]
+    pub fn below(&mut self, bound: usize) -> usize {
+        ((self.next_u64() as u128 * bound as u128) >> 64) as usize
+    }
+}
\ No newline at end of file
@@ -0,0 +1,261 @@
+use crate::chess::{board::Board, castle::{Castle, CastleKind}, color::Color, file::File, moves::{Move, MoveKind}, piece::{Piece, PieceKind}, rank::Rank, square::Square};
+
+// A deliberately simple mailbox move generator, shares nothing with the bitboard generator except the board types
+const KNIGHT_OFFSETS: [(i8, i8); 8] = [(1, 2), (2, 1), (2, -1), (1, -2), (-1, -2), (-2, -1), (-2, 1), (-1, 2)];
+const KING_OFFSETS: [(i8, i8); 8] = [(1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0), (-1, -1), (0, -1), (1, -1)];
+const BISHOP_OFFSETS: [(i8, i8); 4] = [(1, 1), (1, -1), (-1, 1), (-1, -1)];
+const ROOK_OFFSETS: [(i8, i8); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+
+const PROMOTIONS: [MoveKind; 4] = [MoveKind::PromotionQueen, MoveKind::PromotionRook, MoveKind::PromotionBishop, MoveKind::PromotionKnight];
+
+#[derive(Debug, Clone)]
+pub struct Mailbox {
+    squares: [Option<Piece>; Square::COUNT],
+    color: Color,
+    castles: Castle,
+    enpassant: Option<Square>
+}
+
+impl Mailbox {
+    pub fn from_board(board: &Board) -> Self {
+        let mut squares = [None; Square::COUNT];
+
+        for square in Square::all() {
+            squares[square] = board.state.at(square);
+        }
+
+        Self {
+            squares,
+            color: board.color,
+            castles: board.state.castles(),
+            enpassant: board.state.enpassant()
+        }
+    }
+
+    fn offset(square: Square, (rank, file): (i8, i8)) -> Option<Square> {
+        let rank = square.rank().value() as i8 + rank;
+        let file = square.file().value() as i8 + file;
+
+        if !(0..8).contains(&rank) || !(0..8).contains(&file) {
+            return None;
+        }
+
+        Some(Square::from_raw(rank as u8 * 8 + file as u8))
+    }
+
+    fn is_piece(&self, square: Square, kind: PieceKind, color: Color) -> bool {
+        self.squares[square] == Some(Piece::new(kind, color))
+    }
+
+    fn is_enemy(&self, square: Square) -> bool {
+        self.squares[square].is_some_and(|piece| piece.color() != self.color)
+    }
+
+    fn king_square(&self, color: Color) -> Option<Square> {
+        Square::all().find(|&square| self.is_piece(square, PieceKind::King, color))
+    }
+
+    pub fn is_attacked(&self, square: Square, by: Color) -> bool {
+        let forward = match by {
+            Color::White => 1,
+            Color::Black => -1
+        };
+
+        for file in [-1, 1] {
+            if Self::offset(square, (-forward, file)).is_some_and(|from| self.is_piece(from, PieceKind::Pawn, by)) {
+                return true;
+            }
+        }
+
+        for offset in KNIGHT_OFFSETS {
+            if Self::offset(square, offset).is_some_and(|from| self.is_piece(from, PieceKind::Knight, by)) {
+                return true;
+            }
+        }
+
+        for offset in KING_OFFSETS {
+            if Self::offset(square, offset).is_some_and(|from| self.is_piece(from, PieceKind::King, by)) {
+                return true;
+            }
+        }
+
+        for (offsets, kind) in [(BISHOP_OFFSETS, PieceKind::Bishop), (ROOK_OFFSETS, PieceKind::Rook)] {
+            for offset in offsets {
+                let mut current = square;
+
+                while let Some(next) = Self::offset(current, offset) {
+                    if let Some(piece) = self.squares[next] {
+                        if piece.color() == by && (piece.kind() == kind || piece.kind() == PieceKind::Queen) {
+                            return true;
+                        }
+
+                        break;
+                    }
+
+                    current = next;
+                }
+            }
+        }
+
+        false
+    }
+
+    fn push_pawn_moves(&self, moves: &mut Vec<Move>, from: Square) {
+        let forward = match self.color {
+            Color::White => 1,
+            Color::Black => -1
+        };
+
+        let push = |moves: &mut Vec<Move>, to: Square| {
+            if to.rank() == Rank::First || to.rank() == Rank::Eighth {
+                for kind in PROMOTIONS {
+                    moves.push(Move::new(from, to, kind));
+                }
+            }
+            else {
+                moves.push(Move::new(from, to, MoveKind::Normal));
+            }
+        };
+
+        if let Some(to) = Self::offset(from, (forward, 0)).filter(|&to| self.squares[to].is_none()) {
+            push(moves, to);
+
+            let double = Self::offset(to, (forward, 0)).filter(|&to| self.squares[to].is_none());
+
+            if let Some(to) = double.filter(|_| from.rank() == Rank::Second.relative(self.color)) {
+                moves.push(Move::new(from, to, MoveKind::Normal));
+            }
+        }
+
+        for file in [-1, 1] {
+            if let Some(to) = Self::offset(from, (forward, file)) {
+                if self.is_enemy(to) {
+                    push(moves, to);
+                }
+
+                if self.enpassant == Some(to) {
+                    moves.push(Move::new(from, to, MoveKind::Enpassant));
+                }
+            }
+        }
+    }
+
+    fn push_castling_moves(&self, moves: &mut Vec<Move>, from: Square) {
+        for castle in CastleKind::all() {
+            let (color, rank) = match castle {
+                CastleKind::WhiteShort | CastleKind::WhiteLong => (Color::White, Rank::First),
+                CastleKind::BlackShort | CastleKind::BlackLong => (Color::Black, Rank::Eighth)
+            };
+
+            if color != self.color || !self.castles.is_allowed(castle) {
+                continue;
+            }
+
+            if from != Square::new(rank, File::E) || !self.is_piece(castle.rook_from(), PieceKind::Rook, color) {
+                continue;
+            }
+
+            let step = if castle.is_short() { 1 } else { -1 };
+            let mut current = from;
+            let mut empty = true;
+
+            while let Some(next) = Self::offset(current, (0, step)) {
+                if next == castle.rook_from() {
+                    break;
+                }
+
+                empty &= self.squares[next].is_none();
+                current = next;
+            }
+
+            // The king may not castle out of or through check, landing in check is left to the legality filter
+            if empty && !self.is_attacked(from, !color) && !self.is_attacked(castle.rook_to(), !color) {
+                moves.push(Move::new(from, castle.king_to(), MoveKind::Castling));
+            }
+        }
+    }
+
+    fn pseudo_moves(&self) -> Vec<Move> {
+        let mut moves = Vec::new();
+
+        for from in Square::all() {
+            let piece = match self.squares[from] {
+                Some(piece) if piece.color() == self.color => piece,
+                _ => continue
+            };
+
+            let (offsets, slide): (&[(i8, i8)], bool) = match piece.kind() {
+                PieceKind::Pawn => {
+                    self.push_pawn_moves(&mut moves, from);
+                    continue;
+                },
+                PieceKind::Knight => (&KNIGHT_OFFSETS, false),
+                PieceKind::Bishop => (&BISHOP_OFFSETS, true),
+                PieceKind::Rook => (&ROOK_OFFSETS, true),
+                PieceKind::Queen => (&KING_OFFSETS, true),
+                PieceKind::King => {
+                    self.push_castling_moves(&mut moves, from);
+                    (&KING_OFFSETS, false)
+                }
+            };
+
+            for &offset in offsets {
+                let mut current = from;
+
+                while let Some(to) = Self::offset(current, offset) {
+                    if self.squares[to].is_some_and(|piece| piece.color() == self.color) {
+                        break;
+                    }
+
+                    moves.push(Move::new(from, to, MoveKind::Normal));
+
+                    if !slide || self.squares[to].is_some() {
+                        break;
+                    }
+
+                    current = to;
+                }
+            }
+        }
+
+        moves
+    }
+
+    pub fn play(&self, mv: Move) -> Self {
+        let mut next = self.clone();
+        let from = mv.from();
+        let to = mv.to();
+        let piece = next.squares[from].take().unwrap();
+
+        next.squares[to] = Some(match mv.promotion_kind() {
+            Some(kind) => Piece::new(kind, self.color),
+            None => piece
+        });
+
+        if mv.is_enpassant() {
+            next.squares[Square::new(from.rank(), to.file())] = None;
+        }
+
+        if mv.is_castling() {
+            let castle = CastleKind::new(self.color, to > from);
+
+            next.squares[castle.rook_to()] = next.squares[castle.rook_from()].take();
+        }
+
+        next.color = !self.color;
+        next.enpassant = None;
+
+        next
+    }
+
+    pub fn legal_moves(&self) -> Vec<Move> {
+        self.pseudo_moves()
+            .into_iter()
+            .filter(|&mv| {
+                let next = self.play(mv);
+
+                next.king_square(self.color).is_some_and(|king| !next.is_attacked(king, !self.color))
+            })
+            .collect()
+    }
+}
\ No newline at end of file
@@ -0,0 +1,20 @@
+// Outcome of a game, from a pgn result token or from the board once the game is over
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum GameResult {
+    WhiteWin,
+    BlackWin,
+    Draw,
+    Unknown
+}
+
+impl GameResult {
+    pub fn from_str(string: &str) -> Option<Self> {
+        match string {
+            "1-0" => Some(Self::WhiteWin),
+            "0-1" => Some(Self::BlackWin),
+            "1/2-1/2" => Some(Self::Draw),
+            "*" => Some(Self::Unknown),
+            _ => None
+        }
+    }
+}
\ No newline at end of file
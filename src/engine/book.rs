@@ -0,0 +1,315 @@
+use std::{collections::HashMap, io::Write};
+
+use crate::chess::{board::Board, color::Color, moves::Move, pgn::{PgnGame, parse_san}, polyglot, random::Random, result::GameResult};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct BookEntry {
+    pub key: u64,
+    pub mv: u16,
+    pub weight: u16,
+    pub learn: u32
+}
+
+#[derive(Debug, Clone)]
+pub struct Book {
+    entries: Vec<BookEntry>
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct BookStats {
+    games: u32,
+    points: u32
+}
+
+// Collects move statistics from games, a win counts 2 points for the side that played the move and a draw 1
+#[derive(Debug, Clone)]
+pub struct BookBuilder {
+    max_ply: usize,
+    min_games: u32,
+    min_score: u32,
+    stats: HashMap<(u64, u16), BookStats>
+}
+
+impl BookEntry {
+    pub const SIZE: usize = 16;
+
+    // Entries are stored big endian
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        Self {
+            key: u64::from_be_bytes(bytes[0..8].try_into().unwrap()),
+            mv: u16::from_be_bytes(bytes[8..10].try_into().unwrap()),
+            weight: u16::from_be_bytes(bytes[10..12].try_into().unwrap()),
+            learn: u32::from_be_bytes(bytes[12..16].try_into().unwrap())
+        }
+    }
+
+    pub fn to_bytes(self) -> [u8; Self::SIZE] {
+        let mut bytes = [0; Self::SIZE];
+
+        bytes[0..8].copy_from_slice(&self.key.to_be_bytes());
+        bytes[8..10].copy_from_slice(&self.mv.to_be_bytes());
+        bytes[10..12].copy_from_slice(&self.weight.to_be_bytes());
+        bytes[12..16].copy_from_slice(&self.learn.to_be_bytes());
+
+        bytes
+    }
+}
+
+impl Book {
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        let mut entries: Vec<BookEntry> = bytes.chunks_exact(BookEntry::SIZE).map(BookEntry::from_bytes).collect();
+
+        // Books are supposed to be sorted already, sorting again keeps a malformed file from breaking the binary search
+        entries.sort_by_key(|entry| entry.key);
+
+        Self { entries }
+    }
+
+    pub fn load(path: &str) -> std::io::Result<Self> {
+        Ok(Self::from_bytes(&std::fs::read(path)?))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    // Every legal book move for the position with its weight
+    pub fn probe(&self, board: &Board) -> Vec<(Move, u16)> {
+        let key = polyglot::key(board);
+        let start = self.entries.partition_point(|entry| entry.key < key);
+
+        self.entries[start..]
+            .iter()
+            .take_while(|entry| entry.key == key)
+            .filter_map(|entry| polyglot::decode(board, entry.mv).map(|mv| (mv, entry.weight)))
+            .collect()
+    }
+
+    // Picks a book move with probability proportional to its weight
+    pub fn pick(&self, board: &Board, random: &mut Random) -> Option<Move> {
+        let moves = self.probe(board);
+        let total: usize = moves.iter().map(|&(_, weight)| weight as usize).sum();
+
+        if total == 0 {
+            return None;
+        }
+
+        let mut target = random.below(total);
+
+        for (mv, weight) in moves {
+            if target < weight as usize {
+                return Some(mv);
+            }
+
+            target -= weight as usize;
+        }
+
+        None
+    }
+}
+
+impl BookBuilder {
+    // The minimum score is a percentage from the point of view of the side playing the move
+    pub fn new(max_ply: usize, min_games: u32, min_score: u32) -> Self {
+        Self {
+            max_ply,
+            min_games,
+            min_score,
+            stats: HashMap::new()
+        }
+    }
+
+    // Games without a result or with a move that can't be replayed are skipped entirely
+    pub fn add_game(&mut self, game: &PgnGame) -> bool {
+        let result = game.result();
+
+        if result == GameResult::Unknown {
+            return false;
+        }
+
+        let Some(mut board) = game.start() else {
+            return false;
+        };
+
+        let mut keys = Vec::new();
+
+        for san in game.moves.iter().take(self.max_ply) {
+            let Some(mv) = parse_san(&board, san) else {
+                return false;
+            };
+
+            let points = match (result, board.color) {
+                (GameResult::Draw, _) => 1,
+                (GameResult::WhiteWin, Color::White) | (GameResult::BlackWin, Color::Black) => 2,
+                _ => 0
+            };
+
+            keys.push((polyglot::key(&board), polyglot::encode(mv), points));
+            board.make(mv);
+        }
+
+        for (key, mv, points) in keys {
+            let stats = self.stats.entry((key, mv)).or_default();
+
+            stats.games += 1;
+            stats.points += points;
+        }
+
+        true
+    }
+
+    // Sorted by key, then by weight with the best move first, the way Polyglot writes them
+    pub fn build(&self) -> Vec<BookEntry> {
+        let mut entries: Vec<(u64, u16, u32)> = self.stats
+            .iter()
+            .filter(|(_, stats)| stats.games >= self.min_games && stats.points * 50 >= self.min_score * stats.games)
+            .map(|(&(key, mv), stats)| (key, mv, stats.points))
+            .collect();
+
+        entries.sort_unstable_by(|a, b| a.0.cmp(&b.0).then(b.2.cmp(&a.2)).then(a.1.cmp(&b.1)));
+
+        let mut result = Vec::with_capacity(entries.len());
+
+        // Points are scaled down per position when they don't fit the 16 bit weight
+        for group in entries.chunk_by(|a, b| a.0 == b.0) {
+            let max = group[0].2.max(u16::MAX as u32) as u64;
+
+            for &(key, mv, points) in group {
+                let weight = (points as u64 * u16::MAX as u64 / max) as u16;
+
+                result.push(BookEntry { key, mv, weight, learn: 0 });
+            }
+        }
+
+        result
+    }
+
+    pub fn write(&self, path: &str) -> std::io::Result<usize> {
+        let entries = self.build();
+        let mut writer = std::io::BufWriter::new(std::fs::File::create(path)?);
+
+        for entry in entries.iter() {
+            writer.write_all(&entry.to_bytes())?;
+        }
+
+        writer.flush()?;
+
+        Ok(entries.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chess::{moves::MoveKind, pgn::PgnReader, square::Square};
+
+    #[test]
+    fn probe_and_pick() {
+        let board = Board::startpos().unwrap();
+        let key = polyglot::key(&board);
+
+        let e4 = Move::new(Square::E2, Square::E4, MoveKind::Normal);
+        let d4 = Move::new(Square::D2, Square::D4, MoveKind::Normal);
+        let a3 = Move::new(Square::A2, Square::A3, MoveKind::Normal);
+
+        let entries = [
+            BookEntry { key: key + 1, mv: polyglot::encode(a3), weight: 100, learn: 0 },
+            BookEntry { key, mv: polyglot::encode(d4), weight: 1, learn: 0 },
+            BookEntry { key: key - 1, mv: polyglot::encode(a3), weight: 100, learn: 0 },
+            BookEntry { key, mv: polyglot::encode(e4), weight: 3, learn: 0 },
+            BookEntry { key, mv: polyglot::encode(a3), weight: 0, learn: 0 }
+        ];
+
+        let bytes: Vec<u8> = entries.iter().flat_map(|entry| entry.to_bytes()).collect();
+        let book = Book::from_bytes(&bytes);
+
+        assert_eq!(book.len(), entries.len());
+        assert_eq!(book.probe(&board), vec![(d4, 1), (e4, 3), (a3, 0)]);
+
+        let mut random = Random::new(33);
+        let mut e4_count = 0;
+
+        for _ in 0..1000 {
+            match book.pick(&board, &mut random) {
+                Some(mv) if mv == e4 => e4_count += 1,
+                Some(mv) => assert_eq!(mv, d4),
+                None => panic!("no book move")
+            }
+        }
+
+        assert!((650..850).contains(&e4_count));
+
+        let board = Board::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1").unwrap();
+
+        assert_eq!(book.pick(&board, &mut random), None);
+    }
+
+    #[test]
+    fn builder() {
+        let pgn = "\
+[Result \"1-0\"]
+
+1. e4 e5 2. Nf3 1-0
+
+[Result \"1/2-1/2\"]
+
+1. e4 c5 2. Nf3 1/2-1/2
+
+[Result \"0-1\"]
+
+1. d4 d5 0-1
+
+[Result \"*\"]
+
+1. c4 *
+
+[Result \"1-0\"]
+
+1. e4 Ke7?? 1-0
+";
+
+        let mut builder = BookBuilder::new(2, 1, 0);
+        let added: Vec<bool> = PgnReader::new(pgn.as_bytes()).map(|game| builder.add_game(&game)).collect();
+
+        assert_eq!(added, [true, true, true, false, false]);
+
+        let book = Book::from_bytes(&builder.build().iter().flat_map(|entry| entry.to_bytes()).collect::<Vec<u8>>());
+        let board = Board::startpos().unwrap();
+
+        let e4 = Move::new(Square::E2, Square::E4, MoveKind::Normal);
+        let d4 = Move::new(Square::D2, Square::D4, MoveKind::Normal);
+
+        assert_eq!(book.len(), 5);
+        assert_eq!(book.probe(&board), vec![(e4, 3), (d4, 0)]);
+
+        let mut board = Board::startpos().unwrap();
+
+        board.make(e4);
+
+        let e5 = Move::new(Square::E7, Square::E5, MoveKind::Normal);
+        let c5 = Move::new(Square::C7, Square::C5, MoveKind::Normal);
+
+        assert_eq!(book.probe(&board), vec![(c5, 1), (e5, 0)]);
+
+        // Only moves scoring at least 50% over at least one game
+        let mut builder = BookBuilder::new(2, 1, 50);
+
+        for game in PgnReader::new(pgn.as_bytes()) {
+            builder.add_game(&game);
+        }
+
+        assert_eq!(builder.build().len(), 3);
+
+        let mut builder = BookBuilder::new(2, 2, 0);
+
+        for game in PgnReader::new(pgn.as_bytes()) {
+            builder.add_game(&game);
+        }
+
+        assert_eq!(builder.build().len(), 1);
+    }
+}
\ No newline at end of file
@@ -1,4 +1,190 @@
-pub struct Data {
-    id: u32,
-    
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+use crate::{chess::{board::Board, moves::Move, piece::{Piece, PieceKind}, square::Square}, engine::{pv::Pv, history::{CONTINUATIONS, CaptureHistory, ContinuationHistory, CounterMoves, QuietHistory, bonus}, stack::Stack}};
+
+// A legal move at the root with the score and line of its last search and the nodes spent below it
+#[derive(Debug, Clone, Copy)]
+pub struct RootMove {
+    pub mv: Move,
+    pub score: i32,
+    pub pv: Pv,
+    pub nodes: u64
+}
+
+// What the search threads share besides the transposition table, each thread publishes its own counters
+pub struct Shared {
+    pub stop: AtomicBool,
+    pub ponder: AtomicBool,
+    nodes: Vec<AtomicU64>,
+    tbhits: Vec<AtomicU64>
+}
+
+// Everything a search thread owns
+pub struct Data {
+    pub id: u32,
+    pub board: Board,
+    pub stack: Box<Stack>,
+    pub root_moves: Vec<RootMove>,
+    pub pv_index: usize,
+    pub quiet_history: QuietHistory,
+    pub capture_history: CaptureHistory,
+    pub counter_moves: CounterMoves,
+    pub continuation_history: ContinuationHistory,
+    pub nodes: u64,
+    pub tbhits: u64,
+    pub seldepth: usize,
+    pub depth: i32,
+    pub nmp_min_ply: usize
+}
+
+impl Shared {
+    pub fn new(threads: usize) -> Self {
+        Self {
+            stop: AtomicBool::new(false),
+            ponder: AtomicBool::new(false),
+            nodes: (0..threads).map(|_| AtomicU64::new(0)).collect(),
+            tbhits: (0..threads).map(|_| AtomicU64::new(0)).collect()
+        }
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.stop.load(Ordering::Relaxed)
+    }
+
+    // Time limits only count once the gui sends ponderhit
+    pub fn is_pondering(&self) -> bool {
+        self.ponder.load(Ordering::Relaxed)
+    }
+
+    pub fn publish(&self, data: &Data) {
+        self.nodes[data.id as usize].store(data.nodes, Ordering::Relaxed);
+        self.tbhits[data.id as usize].store(data.tbhits, Ordering::Relaxed);
+    }
+
+    pub fn nodes(&self) -> u64 {
+        self.nodes.iter().map(|nodes| nodes.load(Ordering::Relaxed)).sum()
+    }
+
+    pub fn tbhits(&self) -> u64 {
+        self.tbhits.iter().map(|tbhits| tbhits.load(Ordering::Relaxed)).sum()
+    }
+}
+
+impl Data {
+    pub fn new(id: u32, board: Board) -> Self {
+        Self {
+            id,
+            board,
+            stack: Box::new(Stack::new()),
+            root_moves: Vec::new(),
+            pv_index: 0,
+            quiet_history: QuietHistory::new(),
+            capture_history: CaptureHistory::new(),
+            counter_moves: CounterMoves::new(),
+            continuation_history: ContinuationHistory::new(),
+            nodes: 0,
+            tbhits: 0,
+            seldepth: 0,
+            depth: 0,
+            nmp_min_ply: 0
+        }
+    }
+
+    // Starts a new search from the position, the history tables are kept
+    pub fn reset(&mut self, board: Board) {
+        self.board = board;
+        *self.stack = Stack::new();
+        self.root_moves.clear();
+        self.nodes = 0;
+        self.tbhits = 0;
+        self.seldepth = 0;
+        self.depth = 0;
+        self.nmp_min_ply = 0;
+    }
+
+    // Moves already reported in this iteration are left out of the next MultiPV lines
+    pub fn is_root_move(&self, mv: Move) -> bool {
+        self.root_moves[self.pv_index..].iter().any(|root| root.mv == mv)
+    }
+
+    pub fn set_root_moves(&mut self, moves: impl Iterator<Item = Move>) {
+        self.root_moves = moves.map(|mv| RootMove { mv, score: 0, pv: Pv::new(), nodes: 0 }).collect();
+        self.pv_index = 0;
+    }
+
+    // Piece and target square of the move made the given number of plies before this one
+    #[inline(always)]
+    pub fn previous(&self, ply: usize, offset: usize) -> Option<(Piece, Square)> {
+        let entry = &self.stack[ply.checked_sub(offset)?];
+
+        entry.mv.is_some().then(|| (entry.piece, entry.mv.to()))
+    }
+
+    // The counter move to the opponent's last move
+    pub fn counter_move(&self, ply: usize) -> Move {
+        self.previous(ply, 1).map_or(Move::NULL, |(piece, square)| self.counter_moves.get(piece, square))
+    }
+
+    // Butterfly and continuation histories of a quiet move
+    pub fn quiet_score(&self, mv: Move, ply: usize) -> i32 {
+        let piece = self.board.state.at(mv.from()).unwrap();
+
+        CONTINUATIONS.iter().filter_map(|&offset| self.previous(ply, offset)).fold(self.quiet_history.get(self.board.color, mv), |score, previous| {
+            score + self.continuation_history.get(previous, piece, mv.to())
+        })
+    }
+
+    // Kind of the captured piece, promotions without a capture use the king since kings are never captured
+    #[inline(always)]
+    fn captured(&self, mv: Move) -> PieceKind {
+        match self.board.state.at(mv.to()) {
+            Some(piece) => piece.kind(),
+            None if mv.is_enpassant() => PieceKind::Pawn,
+            None => PieceKind::King
+        }
+    }
+
+    pub fn noisy_score(&self, mv: Move) -> i32 {
+        self.capture_history.get(self.board.state.at(mv.from()).unwrap(), mv.to(), self.captured(mv))
+    }
+
+    // Rewards the quiet move that caused a cutoff and punishes the quiet moves tried before it
+    pub fn update_quiets(&mut self, ply: usize, best: Move, quiets: &[Move], depth: i32) {
+        let bonus = bonus(depth);
+        let killers = &mut self.stack[ply].killers;
+
+        if killers[0] != best {
+            killers[1] = killers[0];
+            killers[0] = best;
+        }
+
+        if let Some((piece, square)) = self.previous(ply, 1) {
+            self.counter_moves.set(piece, square, best);
+        }
+
+        for mv in std::iter::once(best).chain(quiets.iter().copied().filter(|&mv| mv != best)) {
+            let bonus = if mv == best { bonus } else { -bonus };
+            let piece = self.board.state.at(mv.from()).unwrap();
+
+            self.quiet_history.update(self.board.color, mv, bonus);
+
+            for offset in CONTINUATIONS {
+                if let Some(previous) = self.previous(ply, offset) {
+                    self.continuation_history.update(previous, piece, mv.to(), bonus);
+                }
+            }
+        }
+    }
+
+    // Rewards the noisy move that caused a cutoff and punishes the other noisy moves tried
+    pub fn update_noisies(&mut self, best: Move, noisies: &[Move], depth: i32) {
+        let bonus = bonus(depth);
+
+        for &mv in noisies {
+            let bonus = if mv == best { bonus } else { -bonus };
+            let piece = self.board.state.at(mv.from()).unwrap();
+
+            self.capture_history.update(piece, mv.to(), self.captured(mv), bonus);
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,235 @@
+use std::{fs::File, io::{BufWriter, Write}, sync::{Mutex, atomic::{AtomicUsize, Ordering}}};
+
+use crate::{chess::{board::Board, color::Color, moves::Move, packed::{PackedBoard, PackedWriter}, random::Random, result::GameResult}, engine::{data::{Data, Shared}, endgame::{self, Endgame}, search::{Limits, Search, TB_WIN_IN_MAX_PLY}, syzygy::{Tablebase, Wdl}, tt::TT}};
+
+// A side is adjudicated the winner once both sides agree on a score this big for this many plies
+const WIN_SCORE: i32 = 2000;
+const WIN_PLIES: usize = 5;
+
+// A quiet score for long enough in a long game is adjudicated a draw
+const DRAW_SCORE: i32 = 10;
+const DRAW_PLIES: usize = 10;
+const DRAW_MIN_PLY: usize = 80;
+
+// Openings the search already sees as lost for one side are thrown away
+const MAX_OPENING_SCORE: i32 = 1000;
+
+// Plays games against itself at a fixed number of nodes per move, scores are from white's point of view
+pub struct Datagen {
+    nodes: u64,
+    random_plies: usize,
+    tablebase: Tablebase
+}
+
+// The recorded positions of a game with their scores
+#[derive(Debug, Clone)]
+pub struct Game {
+    pub positions: Vec<(String, i32)>,
+    pub result: GameResult
+}
+
+impl Game {
+    // One "fen | score | result" line per position, the result is 1.0, 0.5 or 0.0 for white
+    pub fn write(&self, writer: &mut impl Write) -> std::io::Result<()> {
+        let result = match self.result {
+            GameResult::WhiteWin => "1.0",
+            GameResult::BlackWin => "0.0",
+            _ => "0.5"
+        };
+
+        for (fen, score) in &self.positions {
+            writeln!(writer, "{} | {} | {}", fen, score, result)?;
+        }
+
+        Ok(())
+    }
+
+    // One packed 32 byte record per position
+    pub fn write_packed(&self, writer: &mut impl Write) -> std::io::Result<()> {
+        let mut writer = PackedWriter::new(writer);
+
+        for (fen, score) in &self.positions {
+            writer.write(&PackedBoard::from_fen(fen, *score, self.result).expect("recorded positions are valid"))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Datagen {
+    pub fn new(nodes: u64, random_plies: usize, tablebase: Tablebase) -> Self {
+        Self {
+            nodes,
+            random_plies,
+            tablebase
+        }
+    }
+
+    // Best move and score for the side to move, the table is shared with the rest of the game
+    fn search(&self, data: &mut Data, tt: &TT, board: &Board) -> (Move, i32) {
+        let shared = Shared::new(1);
+
+        data.reset(board.clone());
+
+        Search::new(data, tt, &self.tablebase, &shared, Limits { nodes: Some(self.nodes), silent: true, ..Limits::new() }).run()
+    }
+
+    // Random legal moves from the start position, half the openings get one more ply so both colors start
+    fn opening(&self, data: &mut Data, tt: &TT, random: &mut Random) -> Board {
+        loop {
+            let mut board = Board::startpos().unwrap();
+            let plies = self.random_plies + random.below(2);
+
+            for _ in 0..plies {
+                let moves: Vec<_> = board.generate_moves().iter_moves().copied().filter(|&mv| board.is_legal(mv)).collect();
+
+                if moves.is_empty() {
+                    break;
+                }
+
+                board.make(moves[random.below(moves.len())]);
+            }
+
+            if board.outcome().is_none() && self.search(data, tt, &board).1.abs() <= MAX_OPENING_SCORE {
+                return board;
+            }
+        }
+    }
+
+    // Plays a game to the end or to an adjudication, only quiet positions out of check with a quiet best move are kept
+    pub fn play(&self, data: &mut Data, tt: &TT, random: &mut Random) -> Game {
+        tt.clear();
+
+        let mut board = self.opening(data, tt, random);
+        let mut positions = Vec::new();
+        let mut wins = 0;
+        let mut losses = 0;
+        let mut draws = 0;
+
+        let result = loop {
+            if let Some(result) = board.outcome() {
+                break result;
+            }
+
+            let white = board.color == Color::White;
+
+            // The tables assume a reset halfmove clock
+            if board.state.halfmove() == 0 && let Some(wdl) = self.tablebase.probe_wdl(&mut board) {
+                break match (wdl, white) {
+                    (Wdl::Win, true) | (Wdl::Loss, false) => GameResult::WhiteWin,
+                    (Wdl::Win, false) | (Wdl::Loss, true) => GameResult::BlackWin,
+                    _ => GameResult::Draw
+                };
+            }
+
+            let (mv, score) = self.search(data, tt, &board);
+            let score = if white { score } else { -score };
+
+            wins = if score >= WIN_SCORE { wins + 1 } else { 0 };
+            losses = if score <= -WIN_SCORE { losses + 1 } else { 0 };
+            draws = if score.abs() <= DRAW_SCORE { draws + 1 } else { 0 };
+
+            if wins >= WIN_PLIES {
+                break GameResult::WhiteWin;
+            }
+
+            if losses >= WIN_PLIES {
+                break GameResult::BlackWin;
+            }
+
+            if draws >= DRAW_PLIES && board.stack.len() >= DRAW_MIN_PLY {
+                break GameResult::Draw;
+            }
+
+            if board.state.checkers().is_empty() && board.is_quiet(mv) && score.abs() < TB_WIN_IN_MAX_PLY {
+                positions.push((board.to_fen(), score));
+            }
+
+            board.make(mv);
+        };
+
+        Game { positions, result }
+    }
+
+    // Plays the games over the threads and appends them to the file, packed when it ends in .bin, returns the number of positions written
+    pub fn run(&self, path: &str, games: usize, threads: usize, seed: u64) -> std::io::Result<usize> {
+        let packed = path.ends_with(".bin");
+
+        // Searches never wait for the built in endgame tables, so they are solved before the first game
+        Endgame::all().for_each(|endgame| {
+            endgame::table(endgame);
+        });
+        let writer = Mutex::new(BufWriter::new(File::options().create(true).append(true).open(path)?));
+        let played = AtomicUsize::new(0);
+        let positions = AtomicUsize::new(0);
+
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..threads.max(1)).map(|id| {
+                let (writer, played, positions) = (&writer, &played, &positions);
+
+                scope.spawn(move || -> std::io::Result<()> {
+                    let tt = TT::new(16);
+                    let mut random = Random::new(seed.wrapping_add(id as u64 * 0x9e3779b97f4a7c15));
+                    let mut data = Data::new(0, Board::startpos().unwrap());
+
+                    while played.fetch_add(1, Ordering::Relaxed) < games {
+                        let game = self.play(&mut data, &tt, &mut random);
+
+                        match packed {
+                            true => game.write_packed(&mut *writer.lock().unwrap())?,
+                            false => game.write(&mut *writer.lock().unwrap())?
+                        }
+
+                        let total = positions.fetch_add(game.positions.len(), Ordering::Relaxed) + game.positions.len();
+
+                        println!("game {:?} with {} positions, {} in total", game.result, game.positions.len(), total);
+                    }
+
+                    Ok(())
+                })
+            }).collect();
+
+            handles.into_iter().try_for_each(|handle| handle.join().expect("datagen thread panicked"))
+        })?;
+
+        writer.into_inner().unwrap().flush()?;
+
+        Ok(positions.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn play() {
+        let datagen = Datagen::new(2000, 8, Tablebase::new());
+        let tt = TT::new(1);
+        let mut data = Data::new(0, Board::startpos().unwrap());
+        let mut random = Random::new(7);
+
+        let game = datagen.play(&mut data, &tt, &mut random);
+
+        assert!(game.result != GameResult::Unknown);
+        assert!(!game.positions.is_empty());
+
+        for (fen, score) in &game.positions {
+            let board = Board::from_fen(fen).unwrap();
+
+            assert!(board.state.checkers().is_empty() && score.abs() < TB_WIN_IN_MAX_PLY, "{}", fen);
+        }
+
+        let mut text = Vec::new();
+
+        game.write(&mut text).unwrap();
+
+        assert_eq!(String::from_utf8(text).unwrap().lines().count(), game.positions.len());
+
+        let mut bytes = Vec::new();
+
+        game.write_packed(&mut bytes).unwrap();
+
+        assert_eq!(bytes.len(), game.positions.len() * PackedBoard::SIZE);
+    }
+}
\ No newline at end of file
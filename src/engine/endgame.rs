@@ -0,0 +1,544 @@
+use std::sync::OnceLock;
+
+use crate::chess::{attack::{bishop_attacks, king_attacks, knight_attacks, pawn_attacks, rook_attacks}, bitboard::Bitboard, board::Board, castle::CastleKind, color::Color, piece::PieceKind, square::Square};
+
+// Endgames of a king and some pieces against a lone king, solved at startup by retrograde analysis
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Endgame {
+    Kpk,
+    Kqk,
+    Krk,
+    Kbnk
+}
+
+// Perfect play result for the side to move, with the plies until mate
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Outcome {
+    Win(i32),
+    Loss(i32),
+    Draw
+}
+
+// Both kings first and then the strong side's pieces in table order, the strong side always plays white
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+struct Setup {
+    squares: [Square; 4],
+    color: Color
+}
+
+// One byte per position and side to move, zero for draws and unused indices, otherwise the plies until mate plus one
+pub struct Table {
+    endgame: Endgame,
+    data: [Vec<u8>; Color::COUNT]
+}
+
+// The a1-d1-d4 triangle, where pawnless tables keep the strong king
+const TRIANGLE: [Square; 10] = [
+    Square::A1, Square::B1, Square::C1, Square::D1,
+    Square::B2, Square::C2, Square::D2,
+    Square::C3, Square::D3,
+    Square::D4
+];
+
+const TRIANGLE_INDEX: [usize; Square::COUNT] = {
+    let mut index = [0; Square::COUNT];
+    let mut i = 0;
+
+    while i < TRIANGLE.len() {
+        index[TRIANGLE[i].index()] = i;
+        i += 1;
+    }
+
+    index
+};
+
+static TABLES: [OnceLock<Table>; Endgame::COUNT] = [const { OnceLock::new() }; Endgame::COUNT];
+
+impl Endgame {
+    pub const COUNT: usize = 4;
+
+    #[inline(always)]
+    pub const fn index(self) -> usize {
+        self as usize
+    }
+
+    pub fn all() -> impl DoubleEndedIterator<Item = Self> {
+        [
+            Self::Kpk,
+            Self::Kqk,
+            Self::Krk,
+            Self::Kbnk
+        ].into_iter()
+    }
+
+    // Pieces of the strong side besides its king
+    pub const fn pieces(self) -> &'static [PieceKind] {
+        match self {
+            Self::Kpk => &[PieceKind::Pawn],
+            Self::Kqk => &[PieceKind::Queen],
+            Self::Krk => &[PieceKind::Rook],
+            Self::Kbnk => &[PieceKind::Bishop, PieceKind::Knight]
+        }
+    }
+
+    #[inline(always)]
+    const fn len(self) -> usize {
+        self.pieces().len() + 2
+    }
+
+    // Pawns mirror files only, other tables reduce by all eight symmetries of the board
+    const fn size(self) -> usize {
+        match self {
+            Self::Kpk => 24 * 64 * 64,
+            _ => TRIANGLE.len() * 64usize.pow(self.len() as u32 - 1)
+        }
+    }
+
+    // The endgame of a position and the color of its strong side
+    pub fn from_board(board: &Board) -> Option<(Self, Color)> {
+        if board.state.occupied().count() > 4 || CastleKind::all().any(|kind| board.state.castles().is_allowed(kind)) {
+            return None;
+        }
+
+        let strong = match board.state.colors(Color::White).count() {
+            1 => Color::Black,
+            _ => Color::White
+        };
+
+        if board.state.colors(!strong).count() != 1 {
+            return None;
+        }
+
+        Self::all().find(|endgame| {
+            board.state.colors(strong).count() == endgame.len() - 1 && endgame.pieces().iter().all(|&kind| {
+                (board.state.pieces(kind) & board.state.colors(strong)).count() == endgame.pieces().iter().filter(|&&other| other == kind).count()
+            })
+        }).map(|endgame| (endgame, strong))
+    }
+
+    // Maps a position to the representative of its symmetry class
+    fn canonical(self, mut setup: Setup) -> Setup {
+        let squares = &mut setup.squares[..self.len()];
+
+        if self == Self::Kpk {
+            if squares[2].file().value() >= 4 {
+                squares.iter_mut().for_each(|square| *square = square.flip_file());
+            }
+
+            return setup;
+        }
+
+        if squares[0].file().value() >= 4 {
+            squares.iter_mut().for_each(|square| *square = square.flip_file());
+        }
+
+        if squares[0].rank().value() >= 4 {
+            squares.iter_mut().for_each(|square| *square = square.flip_rank());
+        }
+
+        // A king on the diagonal leaves the choice to the first piece off it
+        let off = squares.iter().find(|square| square.rank().value() != square.file().value()).unwrap_or(&squares[0]);
+        let flip = off.rank().value() > off.file().value();
+
+        if flip {
+            squares.iter_mut().for_each(|square| *square = square.flip_diagonal());
+        }
+
+        setup
+    }
+
+    fn encode(self, setup: &Setup) -> usize {
+        let setup = self.canonical(*setup);
+        let squares = &setup.squares;
+
+        match self {
+            Self::Kpk => ((squares[2].rank().index() - 1) * 4 + squares[2].file().index()) * 4096 + squares[0].index() * 64 + squares[1].index(),
+            _ => squares[1..self.len()].iter().fold(TRIANGLE_INDEX[squares[0].index()], |index, square| index * 64 + square.index())
+        }
+    }
+
+    fn decode(self, mut index: usize, color: Color) -> Setup {
+        let mut squares = [Square::A1; 4];
+
+        match self {
+            Self::Kpk => {
+                squares[1] = Square::from_raw((index % 64) as u8);
+                squares[0] = Square::from_raw((index / 64 % 64) as u8);
+                index /= 4096;
+                squares[2] = Square::from_raw(((index / 4 + 1) * 8 + index % 4) as u8);
+            },
+            _ => {
+                for i in (1..self.len()).rev() {
+                    squares[i] = Square::from_raw((index % 64) as u8);
+                    index /= 64;
+                }
+
+                squares[0] = TRIANGLE[index];
+            }
+        }
+
+        Setup { squares, color }
+    }
+
+    fn occupied(self, setup: &Setup) -> Bitboard {
+        setup.squares[..self.len()].iter().fold(Bitboard::new(), |occupied, &square| occupied | Bitboard::from_square(square))
+    }
+
+    // Squares attacked by the strong side's pieces besides its king, skipping a captured one
+    fn attacks(self, setup: &Setup, occupied: Bitboard, captured: Option<Square>) -> Bitboard {
+        self.pieces().iter().zip(&setup.squares[2..]).filter(|&(_, &square)| Some(square) != captured).fold(Bitboard::new(), |attacks, (&kind, &square)| {
+            attacks | match kind {
+                PieceKind::Pawn => pawn_attacks(square, Color::White),
+                PieceKind::Knight => knight_attacks(square),
+                PieceKind::Bishop => bishop_attacks(square, occupied),
+                PieceKind::Rook => rook_attacks(square, occupied),
+                PieceKind::Queen => bishop_attacks(square, occupied) | rook_attacks(square, occupied),
+                PieceKind::King => king_attacks(square)
+            }
+        })
+    }
+
+    fn is_legal(self, setup: &Setup) -> bool {
+        let squares = &setup.squares;
+        let occupied = self.occupied(setup);
+
+        occupied.count() == self.len() &&
+        squares[0].chebyshev(squares[1]) > 1 &&
+        (self != Self::Kpk || (1..7).contains(&squares[2].rank().index())) &&
+        (setup.color == Color::Black || !self.attacks(setup, occupied, None).is_set(squares[1]))
+    }
+
+    fn is_check(self, setup: &Setup) -> bool {
+        self.attacks(setup, self.occupied(setup), None).is_set(setup.squares[1])
+    }
+
+    // Squares the lone king can move to and whether it can take a piece instead, which always draws
+    fn weak_moves(self, setup: &Setup) -> (Bitboard, bool) {
+        let squares = &setup.squares;
+        let occupied = self.occupied(setup) ^ Bitboard::from_square(squares[1]);
+        let mut targets = Bitboard::new();
+        let mut capture = false;
+
+        for to in king_attacks(squares[1]) & !king_attacks(squares[0]) {
+            let captured = occupied.is_set(to).then_some(to);
+
+            if !self.attacks(setup, occupied, captured).is_set(to) {
+                match captured {
+                    Some(_) => capture = true,
+                    None => targets.set(to)
+                }
+            }
+        }
+
+        (targets, capture)
+    }
+
+    // Legal positions one move earlier, with the other side to move, ignoring captures and promotions
+    fn unmoves(self, setup: &Setup, mut f: impl FnMut(Setup)) {
+        let occupied = self.occupied(setup);
+        let color = !setup.color;
+
+        let movers = match color {
+            Color::White => (2..self.len()).chain(0..1),
+            Color::Black => (self.len()..self.len()).chain(1..2)
+        };
+
+        for i in movers {
+            let square = setup.squares[i];
+
+            let from = match i {
+                0 | 1 => king_attacks(square),
+                _ => match self.pieces()[i - 2] {
+                    PieceKind::Pawn => {
+                        let mut from = Bitboard::new();
+                        let single = Square::from_raw(square.value() - 8);
+
+                        if square.rank().index() >= 2 && !occupied.is_set(single) {
+                            from.set(single);
+
+                            if square.rank().index() == 3 && !occupied.is_set(Square::from_raw(square.value() - 16)) {
+                                from.set(Square::from_raw(square.value() - 16));
+                            }
+                        }
+
+                        from
+                    },
+                    PieceKind::Knight => knight_attacks(square),
+                    PieceKind::Bishop => bishop_attacks(square, occupied),
+                    PieceKind::Rook => rook_attacks(square, occupied),
+                    PieceKind::Queen => bishop_attacks(square, occupied) | rook_attacks(square, occupied),
+                    PieceKind::King => king_attacks(square)
+                }
+            };
+
+            for from in from & !occupied {
+                let mut previous = *setup;
+
+                previous.squares[i] = from;
+                previous.color = color;
+
+                if self.is_legal(&previous) {
+                    f(previous);
+                }
+            }
+        }
+    }
+
+    // Plies until mate after the best promotion, looked up in the queen and rook tables
+    fn promotion(self, setup: &Setup) -> Option<usize> {
+        let squares = &setup.squares;
+
+        if self != Self::Kpk || squares[2].rank().index() != 6 {
+            return None;
+        }
+
+        let to = Square::from_raw(squares[2].value() + 8);
+
+        if self.occupied(setup).is_set(to) {
+            return None;
+        }
+
+        [Self::Kqk, Self::Krk].into_iter().filter_map(|endgame| {
+            let next = Setup { squares: [squares[0], squares[1], to, Square::A1], color: Color::Black };
+
+            match table(endgame).data[Color::Black][endgame.encode(&next)] {
+                0 => None,
+                value => Some(value as usize)
+            }
+        }).min()
+    }
+}
+
+impl Table {
+    // Positions are solved a ply at a time, wins from unmoves of lost positions and losses from unmoves of won ones
+    // once every escape of the lone king is known to lose
+    pub fn generate(endgame: Endgame) -> Self {
+        let size = endgame.size();
+        let mut white = vec![0u8; size];
+        let mut black = vec![0u8; size];
+        let mut frontier = Vec::new();
+        let mut promotions: Vec<Vec<usize>> = Vec::new();
+
+        for (index, value) in black.iter_mut().enumerate() {
+            let setup = endgame.decode(index, Color::Black);
+
+            if endgame.encode(&setup) != index {
+                continue;
+            }
+
+            if endgame.is_legal(&setup) && endgame.weak_moves(&setup) == (Bitboard::new(), false) && endgame.is_check(&setup) {
+                *value = 1;
+                frontier.push(index);
+            }
+
+            let setup = Setup { color: Color::White, ..setup };
+
+            if endgame.is_legal(&setup) && let Some(plies) = endgame.promotion(&setup) {
+                promotions.resize(promotions.len().max(plies + 1), Vec::new());
+                promotions[plies].push(index);
+            }
+        }
+
+        let mut ply = 0;
+
+        while !frontier.is_empty() || ply + 1 < promotions.len() {
+            ply += 1;
+
+            let value = u8::try_from(ply + 1).expect("mate too long for the table");
+            let mut next = Vec::new();
+
+            if ply % 2 == 1 {
+                for &index in frontier.iter() {
+                    endgame.unmoves(&endgame.decode(index, Color::Black), |previous| {
+                        let index = endgame.encode(&previous);
+
+                        if white[index] == 0 {
+                            white[index] = value;
+                            next.push(index);
+                        }
+                    });
+                }
+
+                for &index in promotions.get(ply).into_iter().flatten() {
+                    if white[index] == 0 {
+                        white[index] = value;
+                        next.push(index);
+                    }
+                }
+            }
+            else {
+                for &index in frontier.iter() {
+                    endgame.unmoves(&endgame.decode(index, Color::White), |previous| {
+                        let index = endgame.encode(&previous);
+
+                        if black[index] == 0 && is_lost(endgame, &previous, &white) {
+                            black[index] = value;
+                            next.push(index);
+                        }
+                    });
+                }
+            }
+
+            frontier = next;
+        }
+
+        Self {
+            endgame,
+            data: [white, black]
+        }
+    }
+
+    fn probe(&self, setup: &Setup) -> Outcome {
+        match (self.data[setup.color][self.endgame.encode(setup)], setup.color) {
+            (0, _) => Outcome::Draw,
+            (value, Color::White) => Outcome::Win(value as i32 - 1),
+            (value, Color::Black) => Outcome::Loss(value as i32 - 1)
+        }
+    }
+}
+
+// Whether every move of the lone king reaches a position already won by the strong side
+fn is_lost(endgame: Endgame, setup: &Setup, white: &[u8]) -> bool {
+    let (targets, capture) = endgame.weak_moves(setup);
+
+    !capture && targets.is_some() && {targets}.all(|to| {
+        let mut next = *setup;
+
+        next.squares[1] = to;
+        next.color = Color::White;
+
+        white[endgame.encode(&next)] != 0
+    })
+}
+
+// Tables are generated the first time they are needed
+pub fn table(endgame: Endgame) -> &'static Table {
+    TABLES[endgame.index()].get_or_init(|| Table::generate(endgame))
+}
+
+// Perfect play result of the position, when it is one of the solved endgames and its table is ready, a search never waits for one
+pub fn probe(board: &Board) -> Option<Outcome> {
+    let (endgame, strong) = Endgame::from_board(board)?;
+    let table = TABLES[endgame.index()].get()?;
+    let mut squares = [Square::A1; 4];
+
+    squares[0] = board.state.king_square(strong).relative(strong);
+    squares[1] = board.state.king_square(!strong).relative(strong);
+
+    for (i, &kind) in endgame.pieces().iter().enumerate() {
+        squares[i + 2] = (board.state.pieces(kind) & board.state.colors(strong)).lsb().relative(strong);
+    }
+
+    let color = match board.color == strong {
+        true => Color::White,
+        false => Color::Black
+    };
+
+    Some(table.probe(&Setup { squares, color }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::syzygy::{Tablebase, Wdl};
+
+    // Longest mates with the strong side to move and a few known positions
+    #[test]
+    fn longest() {
+        Endgame::all().for_each(|endgame| {
+            table(endgame);
+        });
+
+        for (endgame, plies) in [(Endgame::Kqk, 19), (Endgame::Krk, 31), (Endgame::Kbnk, 65)] {
+            assert_eq!(table(endgame).data[Color::White].iter().max().map(|&value| value as i32 - 1), Some(plies));
+        }
+
+        for (fen, outcome) in [
+            ("4k3/8/8/4K3/4P3/8/8/8 w - - 0 1", Outcome::Win(25)),
+            ("4k3/8/8/4K3/4P3/8/8/8 b - - 0 1", Outcome::Draw),
+            ("8/8/8/8/4p3/4k3/8/4K3 w - - 0 1", Outcome::Loss(24)),
+            ("7k/8/5KQ1/8/8/8/8/8 b - - 0 1", Outcome::Draw),
+            ("8/8/8/8/8/8/1k6/R3K3 w - - 0 1", Outcome::Win(19)),
+            ("k7/8/8/8/8/8/8/1K1BN3 b - - 0 1", Outcome::Loss(60))
+        ] {
+            assert_eq!(probe(&Board::from_fen(fen).unwrap()), Some(outcome), "{}", fen);
+        }
+
+        assert_eq!(probe(&Board::from_fen("4k3/8/8/8/8/8/8/R3K3 w Q - 0 1").unwrap()), None);
+        assert_eq!(probe(&Board::startpos().unwrap()), None);
+    }
+
+    // Every few positions are checked against the Syzygy tables from TEA_SYZYGY_PATH or res/syzygy with cargo test -- --ignored
+    #[test]
+    #[ignore = "needs the syzygy tables"]
+    fn syzygy() {
+        let mut tablebase = Tablebase::new();
+        let mut probed = 0;
+
+        tablebase.add_path(&std::env::var("TEA_SYZYGY_PATH").unwrap_or("res/syzygy".to_string()));
+
+        for endgame in Endgame::all() {
+            table(endgame);
+
+            for index in (0..endgame.size()).step_by(97) {
+                for color in [Color::White, Color::Black] {
+                    let setup = endgame.decode(index, color);
+
+                    if endgame.encode(&setup) != index || !endgame.is_legal(&setup) {
+                        continue;
+                    }
+
+                    let mut board = Board::from_fen(&fen(endgame, &setup)).unwrap();
+                    let outcome = probe(&board).unwrap();
+
+                    let Some(wdl) = tablebase.probe_wdl(&mut board) else {
+                        continue;
+                    };
+
+                    let expected = match outcome {
+                        Outcome::Win(_) => Wdl::Win,
+                        Outcome::Loss(_) => Wdl::Loss,
+                        Outcome::Draw => Wdl::Draw
+                    };
+
+                    assert_eq!(wdl, expected, "{}", board.to_fen());
+
+                    probed += 1;
+                }
+            }
+        }
+
+        assert!(probed > 0, "no syzygy tables found");
+    }
+
+    fn fen(endgame: Endgame, setup: &Setup) -> String {
+        let mut pieces = [' '; Square::COUNT];
+
+        pieces[setup.squares[0].index()] = 'K';
+        pieces[setup.squares[1].index()] = 'k';
+
+        for (&kind, square) in endgame.pieces().iter().zip(&setup.squares[2..]) {
+            pieces[square.index()] = kind.to_string().to_ascii_uppercase().chars().next().unwrap();
+        }
+
+        let ranks: Vec<String> = pieces.chunks(8).rev().map(|rank| {
+            rank.iter().fold(String::new(), |mut fen, &piece| {
+                match (piece, fen.pop()) {
+                    (' ', Some(empty @ '1'..='7')) => fen.push((empty as u8 + 1) as char),
+                    (' ', last) => {
+                        fen.extend(last);
+                        fen.push('1');
+                    },
+                    (piece, last) => {
+                        fen.extend(last);
+                        fen.push(piece);
+                    }
+                }
+
+                fen
+            })
+        }).collect();
+
+        format!("{} {} - - 0 1", ranks.join("/"), if setup.color == Color::White { 'w' } else { 'b' })
+    }
+}
\ No newline at end of file
@@ -0,0 +1,264 @@
+use crate::chess::{board::Board, color::Color, piece::PieceKind, square::Square};
+
+// Game phase weight of each piece, the middlegame is a full 24
+const PHASE: [i32; PieceKind::COUNT] = [0, 1, 1, 2, 4, 0];
+pub const PHASE_MAX: i32 = 24;
+
+// Material and piece square terms, in the order the tuner sees them
+pub const FEATURES: usize = PieceKind::COUNT * (Square::COUNT + 1);
+
+const MATERIAL_MG: [i32; PieceKind::COUNT] = [82, 337, 365, 477, 1025, 0];
+const MATERIAL_EG: [i32; PieceKind::COUNT] = [94, 281, 297, 512, 936, 0];
+
+// Piece square tables from white's point of view, starting at a1
+const PST_MG: [[i32; Square::COUNT]; PieceKind::COUNT] = [
+    // Pawn
+    [
+           0,    0,    0,    0,    0,    0,    0,    0,
+           0,    0,    0,  -10,  -10,    0,    0,    0,
+           3,    3,    3,    3,    3,    3,    3,    3,
+           6,   12,   18,   24,   24,   18,   12,    6,
+           9,   15,   21,   27,   27,   21,   15,    9,
+          12,   18,   24,   30,   30,   24,   18,   12,
+          15,   21,   27,   33,   33,   27,   21,   15,
+           0,    0,    0,    0,    0,    0,    0,    0
+    ],
+    // Knight
+    [
+         -32,  -26,  -20,  -14,  -14,  -20,  -26,  -32,
+         -26,  -20,  -14,   -8,   -8,  -14,  -20,  -26,
+         -20,  -14,   -8,   -2,   -2,   -8,  -14,  -20,
+         -14,   -8,   -2,    4,    4,   -2,   -8,  -14,
+         -14,   -8,   -2,    4,    4,   -2,   -8,  -14,
+         -20,  -14,   -8,   -2,   -2,   -8,  -14,  -20,
+         -26,  -20,  -14,   -8,   -8,  -14,  -20,  -26,
+         -32,  -26,  -20,  -14,  -14,  -20,  -26,  -32
+    ],
+    // Bishop
+    [
+          -7,  -10,   -7,   -4,   -4,   -7,  -10,   -7,
+         -10,   -1,   -4,   -1,   -1,   -4,   -1,  -10,
+          -7,   -4,    5,    2,    2,    5,   -4,   -7,
+          -4,   -1,    2,   11,   11,    2,   -1,   -4,
+          -4,   -1,    2,   11,   11,    2,   -1,   -4,
+          -7,   -4,    5,    2,    2,    5,   -4,   -7,
+         -10,   -1,   -4,   -1,   -1,   -4,   -1,  -10,
+          -7,  -10,   -7,   -4,   -4,   -7,  -10,   -7
+    ],
+    // Rook
+    [
+           0,    0,    0,    6,    6,    0,    0,    0,
+          -4,    0,    0,    0,    0,    0,    0,   -4,
+          -4,    0,    0,    0,    0,    0,    0,   -4,
+          -4,    0,    0,    0,    0,    0,    0,   -4,
+          -4,    0,    0,    0,    0,    0,    0,   -4,
+          -4,    0,    0,    0,    0,    0,    0,   -4,
+          11,   15,   15,   15,   15,   15,   15,   11,
+          -4,    0,    0,    0,    0,    0,    0,   -4
+    ],
+    // Queen
+    [
+         -10,   -8,   -6,   -9,   -4,   -6,   -8,  -10,
+          -8,   -6,   -4,   -2,   -2,   -4,   -6,   -8,
+          -6,   -4,   -2,    0,    0,   -2,   -4,   -6,
+          -4,   -2,    0,    2,    2,    0,   -2,   -4,
+          -4,   -2,    0,    2,    2,    0,   -2,   -4,
+          -6,   -4,   -2,    0,    0,   -2,   -4,   -6,
+          -8,   -6,   -4,   -2,   -2,   -4,   -6,   -8,
+         -10,   -8,   -6,   -4,   -4,   -6,   -8,  -10
+    ],
+    // King
+    [
+          20,   30,   10,    0,    0,   10,   30,   20,
+           0,    0,    0,    0,    0,    0,    0,    0,
+         -22,  -22,  -22,  -22,  -22,  -22,  -22,  -22,
+         -34,  -34,  -34,  -34,  -34,  -34,  -34,  -34,
+         -46,  -46,  -46,  -46,  -46,  -46,  -46,  -46,
+         -58,  -58,  -58,  -58,  -58,  -58,  -58,  -58,
+         -70,  -70,  -70,  -70,  -70,  -70,  -70,  -70,
+         -82,  -82,  -82,  -82,  -82,  -82,  -82,  -82
+    ]
+];
+
+const PST_EG: [[i32; Square::COUNT]; PieceKind::COUNT] = [
+    // Pawn
+    [
+           0,    0,    0,    0,    0,    0,    0,    0,
+           0,    0,    0,    0,    0,    0,    0,    0,
+           4,    4,    4,    4,    4,    4,    4,    4,
+          16,   16,   16,   16,   16,   16,   16,   16,
+          36,   36,   36,   36,   36,   36,   36,   36,
+          64,   64,   64,   64,   64,   64,   64,   64,
+         100,  100,  100,  100,  100,  100,  100,  100,
+           0,    0,    0,    0,    0,    0,    0,    0
+    ],
+    // Knight
+    [
+         -25,  -20,  -15,  -10,  -10,  -15,  -20,  -25,
+         -20,  -15,  -10,   -5,   -5,  -10,  -15,  -20,
+         -15,  -10,   -5,    0,    0,   -5,  -10,  -15,
+         -10,   -5,    0,    5,    5,    0,   -5,  -10,
+         -10,   -5,    0,    5,    5,    0,   -5,  -10,
+         -15,  -10,   -5,    0,    0,   -5,  -10,  -15,
+         -20,  -15,  -10,   -5,   -5,  -10,  -15,  -20,
+         -25,  -20,  -15,  -10,  -10,  -15,  -20,  -25
+    ],
+    // Bishop
+    [
+         -13,  -10,   -7,   -4,   -4,   -7,  -10,  -13,
+         -10,   -7,   -4,   -1,   -1,   -4,   -7,  -10,
+          -7,   -4,   -1,    2,    2,   -1,   -4,   -7,
+          -4,   -1,    2,    5,    5,    2,   -1,   -4,
+          -4,   -1,    2,    5,    5,    2,   -1,   -4,
+          -7,   -4,   -1,    2,    2,   -1,   -4,   -7,
+         -10,   -7,   -4,   -1,   -1,   -4,   -7,  -10,
+         -13,  -10,   -7,   -4,   -4,   -7,  -10,  -13
+    ],
+    // Rook
+    [
+           0,    0,    0,    0,    0,    0,    0,    0,
+           0,    0,    0,    0,    0,    0,    0,    0,
+           0,    0,    0,    0,    0,    0,    0,    0,
+           0,    0,    0,    0,    0,    0,    0,    0,
+           0,    0,    0,    0,    0,    0,    0,    0,
+           0,    0,    0,    0,    0,    0,    0,    0,
+           8,    8,    8,    8,    8,    8,    8,    8,
+           0,    0,    0,    0,    0,    0,    0,    0
+    ],
+    // Queen
+    [
+         -18,  -14,  -10,   -6,   -6,  -10,  -14,  -18,
+         -14,  -10,   -6,   -2,   -2,   -6,  -10,  -14,
+         -10,   -6,   -2,    2,    2,   -2,   -6,  -10,
+          -6,   -2,    2,    6,    6,    2,   -2,   -6,
+          -6,   -2,    2,    6,    6,    2,   -2,   -6,
+         -10,   -6,   -2,    2,    2,   -2,   -6,  -10,
+         -14,  -10,   -6,   -2,   -2,   -6,  -10,  -14,
+         -18,  -14,  -10,   -6,   -6,  -10,  -14,  -18
+    ],
+    // King
+    [
+         -36,  -28,  -20,  -12,  -12,  -20,  -28,  -36,
+         -28,  -20,  -12,   -4,   -4,  -12,  -20,  -28,
+         -20,  -12,   -4,    4,    4,   -4,  -12,  -20,
+         -12,   -4,    4,   12,   12,    4,   -4,  -12,
+         -12,   -4,    4,   12,   12,    4,   -4,  -12,
+         -20,  -12,   -4,    4,    4,   -4,  -12,  -20,
+         -28,  -20,  -12,   -4,   -4,  -12,  -20,  -28,
+         -36,  -28,  -20,  -12,  -12,  -20,  -28,  -36
+    ]
+];
+
+
+// How often each term counts in a position from white's point of view, the evaluation is linear in them
+#[derive(Debug, Clone)]
+pub struct Trace {
+    pub terms: Vec<(u16, i8)>,
+    pub phase: i32
+}
+
+#[inline(always)]
+pub const fn material_index(kind: PieceKind) -> usize {
+    kind.index()
+}
+
+#[inline(always)]
+pub const fn pst_index(kind: PieceKind, square: Square) -> usize {
+    PieceKind::COUNT + kind.index() * Square::COUNT + square.index()
+}
+
+// Middlegame and endgame value of every term
+pub fn weights() -> Vec<[i32; 2]> {
+    let mut weights = vec![[0; 2]; FEATURES];
+
+    for kind in PieceKind::all() {
+        weights[material_index(kind)] = [MATERIAL_MG[kind], MATERIAL_EG[kind]];
+
+        for square in Square::all() {
+            weights[pst_index(kind, square)] = [PST_MG[kind][square], PST_EG[kind][square]];
+        }
+    }
+
+    weights
+}
+
+// Same walk as the evaluation, counting terms instead of adding them up
+pub fn trace(board: &Board) -> Trace {
+    let mut coefficients = [0i8; FEATURES];
+    let mut phase = 0;
+
+    for color in [Color::White, Color::Black] {
+        let sign = if color == Color::White { 1 } else { -1 };
+
+        for kind in PieceKind::all() {
+            let pieces = board.state.pieces(kind) & board.state.colors(color);
+
+            for square in pieces {
+                let square = square.relative(color);
+
+                coefficients[material_index(kind)] += sign;
+                coefficients[pst_index(kind, square)] += sign;
+            }
+
+            phase += PHASE[kind] * pieces.count() as i32;
+        }
+    }
+
+    Trace {
+        terms: coefficients.iter().enumerate().filter(|&(_, &coefficient)| coefficient != 0).map(|(i, &coefficient)| (i as u16, coefficient)).collect(),
+        phase: phase.min(PHASE_MAX)
+    }
+}
+
+// Tapered material and piece square evaluation from the side to move's point of view
+pub fn evaluate(board: &Board) -> i32 {
+    let mut mg = 0;
+    let mut eg = 0;
+    let mut phase = 0;
+
+    for color in [Color::White, Color::Black] {
+        let sign = if color == Color::White { 1 } else { -1 };
+
+        for kind in PieceKind::all() {
+            let pieces = board.state.pieces(kind) & board.state.colors(color);
+
+            for square in pieces {
+                let square = square.relative(color);
+
+                mg += sign * (MATERIAL_MG[kind] + PST_MG[kind][square]);
+                eg += sign * (MATERIAL_EG[kind] + PST_EG[kind][square]);
+            }
+
+            phase += PHASE[kind] * pieces.count() as i32;
+        }
+    }
+
+    let phase = phase.min(PHASE_MAX);
+    let score = (mg * phase + eg * (PHASE_MAX - phase)) / PHASE_MAX;
+
+    match board.color {
+        Color::White => score,
+        Color::Black => -score
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trace() {
+        let weights = weights();
+
+        for fen in ["rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R b KQkq - 0 1", "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1"] {
+            let board = Board::from_fen(fen).unwrap();
+            let trace = super::trace(&board);
+
+            let mg: i32 = trace.terms.iter().map(|&(i, coefficient)| weights[i as usize][0] * coefficient as i32).sum();
+            let eg: i32 = trace.terms.iter().map(|&(i, coefficient)| weights[i as usize][1] * coefficient as i32).sum();
+            let score = (mg * trace.phase + eg * (PHASE_MAX - trace.phase)) / PHASE_MAX;
+
+            assert_eq!(if board.color == Color::White { score } else { -score }, evaluate(&board), "{}", fen);
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,148 @@
+use crate::chess::{color::Color, moves::Move, piece::{Piece, PieceKind}, square::Square};
+
+pub const MAX_HISTORY: i32 = 16384;
+
+// Plies back of the moves followed by continuation history
+pub const CONTINUATIONS: [usize; 3] = [1, 2, 4];
+
+// Butterfly history of quiet moves by side to move
+#[derive(Debug, Clone)]
+pub struct QuietHistory {
+    data: [[i16; 4096]; Color::COUNT]
+}
+
+// History of noisy moves by moving piece, target square and captured kind
+#[derive(Debug, Clone)]
+pub struct CaptureHistory {
+    data: [[[i16; PieceKind::COUNT]; Square::COUNT]; Piece::COUNT]
+}
+
+// The quiet move that refuted each piece and target square
+#[derive(Debug, Clone)]
+pub struct CounterMoves {
+    data: [[Move; Square::COUNT]; Piece::COUNT]
+}
+
+// History of a piece and target square, following the piece and target square of an earlier move
+#[derive(Debug, Clone)]
+pub struct ContinuationHistory {
+    data: Box<[i16]>
+}
+
+// Bonus for the move that caused a cutoff, the other quiet moves get the same malus
+#[inline(always)]
+pub fn bonus(depth: i32) -> i32 {
+    (depth * 200 - 100).clamp(0, 2000)
+}
+
+// Bigger updates move the entry less the closer it already is to the bound
+#[inline(always)]
+fn gravity(entry: &mut i16, bonus: i32) {
+    let bonus = bonus.clamp(-MAX_HISTORY, MAX_HISTORY);
+
+    *entry += (bonus - *entry as i32 * bonus.abs() / MAX_HISTORY) as i16;
+}
+
+impl QuietHistory {
+    pub fn new() -> Self {
+        Self {
+            data: [[0; 4096]; Color::COUNT]
+        }
+    }
+
+    #[inline(always)]
+    pub fn get(&self, color: Color, mv: Move) -> i32 {
+        self.data[color][mv.encode()] as i32
+    }
+
+    #[inline(always)]
+    pub fn update(&mut self, color: Color, mv: Move, bonus: i32) {
+        gravity(&mut self.data[color][mv.encode()], bonus);
+    }
+}
+
+impl CaptureHistory {
+    pub fn new() -> Self {
+        Self {
+            data: [[[0; PieceKind::COUNT]; Square::COUNT]; Piece::COUNT]
+        }
+    }
+
+    #[inline(always)]
+    pub fn get(&self, piece: Piece, square: Square, captured: PieceKind) -> i32 {
+        self.data[piece][square][captured] as i32
+    }
+
+    #[inline(always)]
+    pub fn update(&mut self, piece: Piece, square: Square, captured: PieceKind, bonus: i32) {
+        gravity(&mut self.data[piece][square][captured], bonus);
+    }
+}
+
+impl CounterMoves {
+    pub fn new() -> Self {
+        Self {
+            data: [[Move::NULL; Square::COUNT]; Piece::COUNT]
+        }
+    }
+
+    #[inline(always)]
+    pub fn get(&self, piece: Piece, square: Square) -> Move {
+        self.data[piece][square]
+    }
+
+    #[inline(always)]
+    pub fn set(&mut self, piece: Piece, square: Square, mv: Move) {
+        self.data[piece][square] = mv;
+    }
+}
+
+impl ContinuationHistory {
+    const SIZE: usize = Piece::COUNT * Square::COUNT;
+
+    pub fn new() -> Self {
+        Self {
+            data: vec![0; Self::SIZE * Self::SIZE].into_boxed_slice()
+        }
+    }
+
+    #[inline(always)]
+    fn index(previous: (Piece, Square), piece: Piece, square: Square) -> usize {
+        (previous.0.index() * Square::COUNT + previous.1.index()) * Self::SIZE + piece.index() * Square::COUNT + square.index()
+    }
+
+    #[inline(always)]
+    pub fn get(&self, previous: (Piece, Square), piece: Piece, square: Square) -> i32 {
+        self.data[Self::index(previous, piece, square)] as i32
+    }
+
+    #[inline(always)]
+    pub fn update(&mut self, previous: (Piece, Square), piece: Piece, square: Square, bonus: i32) {
+        gravity(&mut self.data[Self::index(previous, piece, square)], bonus);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chess::moves::MoveKind;
+
+    #[test]
+    fn gravity() {
+        let mut history = QuietHistory::new();
+        let mv = Move::new(Square::G1, Square::F3, MoveKind::Normal);
+
+        for _ in 0..1000 {
+            history.update(Color::White, mv, bonus(20));
+        }
+
+        assert!(history.get(Color::White, mv) > 0 && history.get(Color::White, mv) <= MAX_HISTORY);
+        assert_eq!(history.get(Color::Black, mv), 0);
+
+        for _ in 0..1000 {
+            history.update(Color::White, mv, -bonus(20));
+        }
+
+        assert!(history.get(Color::White, mv) < 0 && history.get(Color::White, mv) >= -MAX_HISTORY);
+    }
+}
\ No newline at end of file
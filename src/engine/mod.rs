@@ -1,4 +1,18 @@
-pub mod pv;
-pub mod stack;
-pub mod data;
-pub mod movepick;
\ No newline at end of file
+pub mod pv;
+pub mod stack;
+pub mod data;
+pub mod eval;
+pub mod history;
+pub mod see;
+pub mod params;
+pub mod tt;
+pub mod movepick;
+pub mod search;
+pub mod timeman;
+pub mod threads;
+pub mod book;
+pub mod syzygy;
+pub mod endgame;
+pub mod tuner;
+pub mod datagen;
+pub mod uci;
\ No newline at end of file
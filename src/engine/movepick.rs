@@ -1,17 +1,219 @@
-use crate::chess::{movelist::MoveList, moves::Move};
-
-#[derive(Copy, Clone, Eq, PartialEq, PartialOrd)]
-pub enum Stage {
-    Hasher,
-    NoisyGen,
-    Noisy,
-    QuietGen,
-    Quiet
-}
-
-pub struct MovePicker {
-    list: MoveList,
-    index: usize,
-    hasher: Move,
-    skip: bool
+use crate::{chess::{movelist::MoveList, moves::Move, piece::PieceKind}, engine::{data::Data, history::MAX_HISTORY, see::{gain, see}}};
+
+// Killers and the counter move are tried before the other quiets, in this order
+const KILLER_SCORE: i32 = 1 << 20;
+
+#[derive(Copy, Clone, Eq, PartialEq, PartialOrd)]
+pub enum Stage {
+    Hasher,
+    NoisyGen,
+    GoodNoisy,
+    QuietGen,
+    Quiet,
+    BadNoisy
+}
+
+pub struct MovePicker {
+    list: MoveList,
+    bad: MoveList,
+    stage: Stage,
+    index: usize,
+    bad_index: usize,
+    hasher: Move,
+    killers: [Move; 2],
+    counter: Move,
+    ply: usize,
+    skip: bool
+}
+
+impl MovePicker {
+    // Quiet moves are skipped in quiescence search, unless the side to move is in check
+    pub fn new(data: &Data, ply: usize, hasher: Move, skip: bool) -> Self {
+        Self {
+            list: MoveList::new(),
+            bad: MoveList::new(),
+            stage: Stage::Hasher,
+            index: 0,
+            bad_index: 0,
+            hasher,
+            killers: data.stack[ply].killers,
+            counter: data.counter_move(ply),
+            ply,
+            skip
+        }
+    }
+
+    pub fn stage(&self) -> Stage {
+        self.stage
+    }
+
+    pub fn skip_quiets(&mut self) {
+        self.skip = true;
+    }
+
+    pub fn is_skipping(&self) -> bool {
+        self.skip
+    }
+
+    // Returns pseudo legal moves, the hash move first and then by stage in order of score, captures losing material come last
+    pub fn next(&mut self, data: &Data) -> Option<Move> {
+        let board = &data.board;
+
+        loop {
+            match self.stage {
+                Stage::Hasher => {
+                    self.stage = Stage::NoisyGen;
+
+                    if board.is_pseudo_legal(self.hasher) && (!self.skip || board.is_noisy(self.hasher)) {
+                        return Some(self.hasher);
+                    }
+                },
+                Stage::NoisyGen => {
+                    self.list = board.generate_noisy_moves();
+
+                    for entry in self.list.iter_mut() {
+                        entry.score = Self::score_noisy(data, entry.mv);
+                    }
+
+                    self.stage = Stage::GoodNoisy;
+                },
+                Stage::GoodNoisy => {
+                    while let Some(mv) = self.select() {
+                        if see(board, mv, 0) {
+                            return Some(mv);
+                        }
+
+                        self.bad.push(mv);
+                    }
+
+                    self.stage = match self.skip {
+                        true => Stage::BadNoisy,
+                        false => Stage::QuietGen
+                    };
+                },
+                Stage::QuietGen => {
+                    let start = self.list.len();
+
+                    for mv in board.generate_quiet_moves().iter_moves() {
+                        self.list.push(*mv);
+                    }
+
+                    for i in start..self.list.len() {
+                        self.list[i].score = self.score_quiet(data, self.list[i].mv);
+                    }
+
+                    self.stage = Stage::Quiet;
+                },
+                Stage::Quiet => {
+                    if !self.skip && let Some(mv) = self.select() {
+                        return Some(mv);
+                    }
+
+                    self.stage = Stage::BadNoisy;
+                },
+                Stage::BadNoisy => {
+                    self.bad_index += 1;
+
+                    return self.bad.get(self.bad_index - 1).map(|entry| entry.mv);
+                }
+            }
+        }
+    }
+
+    // Most valuable victim and least valuable attacker first, capture history orders the ties
+    fn score_noisy(data: &Data, mv: Move) -> i32 {
+        let attacker = data.board.state.at(mv.from()).unwrap().kind();
+
+        (gain(&data.board, mv) * PieceKind::COUNT as i32 - attacker.index() as i32) * MAX_HISTORY * 2 + data.noisy_score(mv)
+    }
+
+    fn score_quiet(&self, data: &Data, mv: Move) -> i32 {
+        match mv {
+            _ if mv == self.killers[0] => KILLER_SCORE + 2,
+            _ if mv == self.killers[1] => KILLER_SCORE + 1,
+            _ if mv == self.counter => KILLER_SCORE,
+            _ => data.quiet_score(mv, self.ply)
+        }
+    }
+
+    // Swaps the best scored of the remaining moves to the front, the hash move was already tried
+    fn select(&mut self) -> Option<Move> {
+        while self.index < self.list.len() {
+            let best = (self.index..self.list.len()).max_by_key(|&i| self.list[i].score).unwrap();
+
+            self.list.swap(self.index, best);
+            self.index += 1;
+
+            let mv = self.list[self.index - 1].mv;
+
+            if mv != self.hasher {
+                return Some(mv);
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chess::{board::Board, color::Color, moves::MoveKind, square::Square};
+
+    #[test]
+    fn stages() {
+        let data = Data::new(0, Board::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1").unwrap());
+        let board = &data.board;
+        let hasher = Move::new(Square::E1, Square::G1, MoveKind::Castling);
+
+        let mut picker = MovePicker::new(&data, 0, hasher, false);
+        let mut moves = Vec::new();
+
+        while let Some(mv) = picker.next(&data) {
+            moves.push(mv);
+        }
+
+        let mut expected: Vec<Move> = board.generate_moves().iter_moves().copied().collect();
+
+        // Winning captures come before the quiets and losing ones after them
+        let good = moves.iter().skip(1).take_while(|&&mv| board.is_noisy(mv)).count();
+        let bad = moves.iter().rev().take_while(|&&mv| board.is_noisy(mv)).count();
+
+        assert_eq!(moves[0], hasher);
+        assert!(moves[1..=good].iter().all(|&mv| see(board, mv, 0)));
+        assert!(bad > 0 && moves[moves.len() - bad..].iter().all(|&mv| !see(board, mv, 0)));
+        assert_eq!(good + bad, board.generate_noisy_moves().len());
+
+        moves.sort_by_key(|mv| mv.value());
+        expected.sort_by_key(|mv| mv.value());
+
+        assert_eq!(moves, expected);
+
+        // A quiet hash move isn't returned when quiets are skipped
+        let mut picker = MovePicker::new(&data, 0, hasher, true);
+        let mut count = 0;
+
+        while let Some(mv) = picker.next(&data) {
+            assert!(board.is_noisy(mv));
+            count += 1;
+        }
+
+        assert_eq!(count, board.generate_noisy_moves().len());
+    }
+
+    #[test]
+    fn quiet_order() {
+        let mut data = Data::new(0, Board::startpos().unwrap());
+        let killers = [Move::new(Square::G1, Square::F3, MoveKind::Normal), Move::new(Square::E2, Square::E4, MoveKind::Normal)];
+        let history = Move::new(Square::D2, Square::D4, MoveKind::Normal);
+
+        data.stack[0].killers = killers;
+        data.quiet_history.update(Color::White, history, 1000);
+
+        let mut picker = MovePicker::new(&data, 0, Move::NULL, false);
+        let moves: Vec<Move> = std::iter::from_fn(|| picker.next(&data)).collect();
+
+        assert_eq!(moves[..3], [killers[0], killers[1], history]);
+        assert_eq!(moves.len(), 20);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,112 @@
+#[cfg(feature = "tune")]
+use std::sync::atomic::{AtomicI32, Ordering};
+
+// Every parameter is a function, a constant unless built with the tune feature which turns them into uci options for spsa
+macro_rules! params {
+    ($($name:ident: $value:expr, $min:expr, $max:expr, $step:expr;)*) => {
+        $(
+            #[cfg(not(feature = "tune"))]
+            #[inline(always)]
+            pub const fn $name() -> i32 {
+                $value
+            }
+
+            #[cfg(feature = "tune")]
+            #[inline(always)]
+            pub fn $name() -> i32 {
+                values::$name.load(Ordering::Relaxed)
+            }
+        )*
+
+        #[cfg(feature = "tune")]
+        #[allow(non_upper_case_globals)]
+        mod values {
+            use super::*;
+
+            $(pub static $name: AtomicI32 = AtomicI32::new($value);)*
+        }
+
+        // Name, default, min, max and step of every parameter
+        pub const PARAMS: &[(&str, i32, i32, i32, i32)] = &[$((stringify!($name), $value, $min, $max, $step)),*];
+
+        // Returns false for unknown names and when parameters are constants
+        #[cfg(feature = "tune")]
+        pub fn set(name: &str, value: i32) -> bool {
+            match name {
+                $(_ if name.eq_ignore_ascii_case(stringify!($name)) => values::$name.store(value.clamp($min, $max), Ordering::Relaxed),)*
+                _ => return false
+            }
+
+            true
+        }
+
+        #[cfg(not(feature = "tune"))]
+        pub fn set(_name: &str, _value: i32) -> bool {
+            false
+        }
+    };
+}
+
+params! {
+    asp_depth: 4, 2, 8, 1;
+    asp_window: 15, 5, 40, 2;
+
+    rfp_depth: 8, 4, 12, 1;
+    rfp_margin: 80, 40, 160, 8;
+    rfp_improving: 70, 30, 140, 8;
+
+    razor_depth: 4, 1, 8, 1;
+    razor_margin: 250, 100, 500, 20;
+
+    nmp_depth: 3, 1, 6, 1;
+    nmp_base: 3, 1, 6, 1;
+    nmp_divisor: 3, 2, 6, 1;
+    nmp_eval_divisor: 200, 100, 400, 20;
+    nmp_verification_depth: 12, 6, 20, 1;
+
+    fp_depth: 8, 4, 12, 1;
+    fp_base: 100, 40, 200, 10;
+    fp_margin: 100, 40, 200, 10;
+
+    lmp_base: 3, 1, 8, 1;
+
+    lmr_depth: 3, 1, 6, 1;
+    lmr_base: 77, 30, 150, 5;
+    lmr_divisor: 236, 150, 350, 10;
+    lmr_history_divisor: 8192, 4096, 16384, 512;
+
+    se_depth: 8, 4, 12, 1;
+    se_margin: 32, 16, 64, 2;
+    se_double_margin: 20, 5, 50, 3;
+    se_triple_margin: 100, 40, 200, 10;
+    se_double_limit: 8, 2, 16, 1;
+
+    see_quiet_margin: 60, 20, 120, 5;
+    see_noisy_margin: 30, 10, 60, 3;
+}
+
+// Spin options for the gui, in the order they are declared
+#[cfg(feature = "tune")]
+pub fn print() {
+    for (name, value, min, max, _) in PARAMS {
+        println!("option name {} type spin default {} min {} max {}", name, value, min, max);
+    }
+}
+
+#[cfg(not(feature = "tune"))]
+pub fn print() {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounds() {
+        for &(name, value, min, max, step) in PARAMS {
+            assert!(min <= value && value <= max && step > 0, "{}", name);
+        }
+
+        assert_eq!(asp_depth(), PARAMS[0].1);
+        assert!(!set("unknown", 0));
+    }
+}
\ No newline at end of file
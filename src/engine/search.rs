@@ -0,0 +1,965 @@
+use arrayvec::ArrayVec;
+
+use std::sync::atomic::Ordering;
+
+use crate::{chess::{color::Color, moves::Move, piece::PieceKind}, engine::{data::{Data, Shared}, endgame::{self, Outcome}, eval::evaluate, movepick::{MovePicker, Stage}, params, see::see, pv::{MAX_PLY, Pv}, syzygy::{Tablebase, Wdl}, timeman::TimeManager, tt::{Bound, TT, TTEntry}}};
+
+pub const INFINITE: i32 = 32000;
+pub const MATE: i32 = 31000;
+pub const DRAW: i32 = 0;
+
+// Mates are counted in plies from the root, so every mate score lies within MAX_PLY of MATE
+pub const MATE_IN_MAX_PLY: i32 = MATE - MAX_PLY as i32;
+
+// Tablebase wins rank below every mate the search can find
+pub const TB_WIN: i32 = MATE - 2 * MAX_PLY as i32;
+pub const TB_WIN_IN_MAX_PLY: i32 = TB_WIN - MAX_PLY as i32;
+
+// Everything given to go, times are in milliseconds, a silent search prints nothing
+#[derive(Debug, Clone, Copy)]
+pub struct Limits {
+    pub depth: i32,
+    pub nodes: Option<u64>,
+    pub movetime: Option<u64>,
+    pub mate: Option<i32>,
+    pub time: [Option<u64>; Color::COUNT],
+    pub inc: [u64; Color::COUNT],
+    pub movestogo: Option<u64>,
+    pub overhead: u64,
+    pub multipv: usize,
+    pub infinite: bool,
+    pub silent: bool
+}
+
+pub struct Search<'a> {
+    data: &'a mut Data,
+    tt: &'a TT,
+    tablebase: &'a Tablebase,
+    shared: &'a Shared,
+    limits: Limits,
+    time: TimeManager,
+    reductions: Box<[[i32; 64]; MAX_PLY]>
+}
+
+impl Limits {
+    pub const MAX_DEPTH: i32 = MAX_PLY as i32 - 1;
+
+    pub fn new() -> Self {
+        Self {
+            depth: Self::MAX_DEPTH,
+            nodes: None,
+            movetime: None,
+            mate: None,
+            time: [None; Color::COUNT],
+            inc: [0; Color::COUNT],
+            movestogo: None,
+            overhead: 0,
+            multipv: 1,
+            infinite: false,
+            silent: false
+        }
+    }
+}
+
+impl<'a> Search<'a> {
+    pub fn new(data: &'a mut Data, tt: &'a TT, tablebase: &'a Tablebase, shared: &'a Shared, limits: Limits) -> Self {
+        let time = TimeManager::new(&limits, data.board.color);
+
+        Self {
+            data,
+            tt,
+            tablebase,
+            shared,
+            limits,
+            time,
+            reductions: reductions()
+        }
+    }
+
+    // Iterative deepening returning the best move with its score, only the main thread prints and manages time
+    pub fn run(&mut self) -> (Move, i32) {
+        let board = &mut self.data.board;
+        let moves: Vec<Move> = board.generate_moves().iter_moves().copied().filter(|&mv| board.is_legal(mv)).collect();
+
+        // Only search the moves that keep the tablebase outcome
+        match self.tablebase.root_moves(board) {
+            Some(moves) if !moves.is_empty() => {
+                self.data.set_root_moves(moves.into_iter());
+                self.data.tbhits += 1;
+            },
+            _ => self.data.set_root_moves(moves.into_iter())
+        }
+
+        if self.data.root_moves.is_empty() {
+            return (Move::NULL, DRAW);
+        }
+
+        // Helpers only look for the best line
+        let main = self.data.id == 0;
+        let lines = if main { self.limits.multipv.clamp(1, self.data.root_moves.len()) } else { 1 };
+        let mut stability = 0;
+
+        for depth in 1..=self.limits.depth.clamp(1, Limits::MAX_DEPTH) {
+            let previous = self.data.root_moves[0].mv;
+
+            self.data.seldepth = 0;
+
+            for pv_index in 0..lines {
+                self.data.pv_index = pv_index;
+
+                let score = self.aspiration(depth);
+
+                // An unfinished line can't be trusted
+                if self.shared.is_stopped() {
+                    break;
+                }
+
+                let pv = self.data.stack[0].pv;
+                let i = self.data.root_moves.iter().position(|root| root.mv == pv.front()).unwrap();
+                let mut root = self.data.root_moves.remove(i);
+
+                root.score = score;
+                root.pv = pv;
+
+                self.data.root_moves.insert(pv_index, root);
+            }
+
+            if self.shared.is_stopped() {
+                break;
+            }
+
+            let best = self.data.root_moves[0];
+
+            stability = if best.mv == previous { stability + 1 } else { 0 };
+
+            self.data.depth = depth;
+            self.shared.publish(self.data);
+
+            if !main {
+                continue;
+            }
+
+            if !self.limits.silent {
+                self.info(depth, lines);
+            }
+
+            // Pondering keeps searching, the gui expects no move before ponderhit or stop
+            if self.shared.is_pondering() {
+                continue;
+            }
+
+            // A mate in the given number of moves or fewer is proven
+            if let Some(mate) = self.limits.mate && best.score >= MATE - (2 * mate - 1) {
+                break;
+            }
+
+            if self.time.is_soft_stop(stability, best.nodes as f64 / self.data.nodes.max(1) as f64) {
+                break;
+            }
+        }
+
+        // An infinite or pondering search only reports its move once told to stop
+        while main && (self.limits.infinite || self.shared.is_pondering()) && !self.shared.is_stopped() {
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+
+        self.shared.publish(self.data);
+
+        (self.data.root_moves[0].mv, self.data.root_moves[0].score)
+    }
+
+    // Searches around the line's last score and widens the failing side until the score lands inside
+    fn aspiration(&mut self, depth: i32) -> i32 {
+        let previous = self.data.root_moves[self.data.pv_index].score;
+        let mut delta = params::asp_window();
+        let (mut alpha, mut beta) = match depth >= params::asp_depth() {
+            true => ((previous - delta).max(-INFINITE), (previous + delta).min(INFINITE)),
+            false => (-INFINITE, INFINITE)
+        };
+
+        loop {
+            let score = self.negamax::<true>(alpha, beta, depth, 0, false);
+
+            if self.shared.is_stopped() {
+                return score;
+            }
+
+            let bound = match score {
+                _ if score <= alpha => Bound::Upper,
+                _ if score >= beta => Bound::Lower,
+                _ => return score
+            };
+
+            // Guis only want the bounds of re-searches that take a while
+            if self.data.id == 0 && !self.limits.silent && self.time.elapsed().as_millis() >= 3000 {
+                let pv = match bound {
+                    Bound::Lower => self.data.stack[0].pv,
+                    _ => self.data.root_moves[self.data.pv_index].pv
+                };
+
+                self.line(depth, self.data.pv_index, score, bound, &pv);
+            }
+
+            match bound {
+                Bound::Upper => {
+                    beta = (alpha + beta) / 2;
+                    alpha = (score - delta).max(-INFINITE);
+                },
+                _ => beta = (score + delta).min(INFINITE)
+            }
+
+            delta += delta / 2;
+        }
+    }
+
+    // Publishes the node count and raises the stop flag once the hard time limit or the node limit is reached, time is ignored while pondering
+    fn check_limits(&self) {
+        if !self.data.nodes.is_multiple_of(1024) {
+            return;
+        }
+
+        self.shared.publish(self.data);
+
+        if self.data.id == 0 && (self.time.is_hard_stop() && !self.shared.is_pondering() || self.limits.nodes.is_some_and(|nodes| self.shared.nodes() >= nodes)) {
+            self.shared.stop.store(true, Ordering::Relaxed);
+        }
+    }
+
+    // One line per MultiPV line, best first
+    fn info(&self, depth: i32, lines: usize) {
+        for (i, root) in self.data.root_moves.iter().take(lines).enumerate() {
+            self.line(depth, i, root.score, Bound::Exact, &root.pv);
+        }
+    }
+
+    // A score outside the aspiration window is only a bound
+    fn line(&self, depth: i32, index: usize, score: i32, bound: Bound, pv: &Pv) {
+        let time = self.time.elapsed().as_millis();
+        let nodes = self.shared.nodes();
+        let nps = nodes as u128 * 1000 / time.max(1);
+
+        let bound = match bound {
+            Bound::Lower => " lowerbound",
+            Bound::Upper => " upperbound",
+            _ => ""
+        };
+
+        println!(
+            "info depth {} seldepth {} multipv {} score {}{} nodes {} nps {} hashfull {} tbhits {} time {} pv {}",
+            depth,
+            self.data.seldepth,
+            index + 1,
+            format_score(score),
+            bound,
+            nodes,
+            nps,
+            self.tt.hashfull(),
+            self.shared.tbhits(),
+            time,
+            pv
+        );
+    }
+
+    fn negamax<const PV: bool>(&mut self, mut alpha: i32, mut beta: i32, depth: i32, ply: usize, cut_node: bool) -> i32 {
+        let root = ply == 0;
+
+        self.data.stack[ply].pv.clear();
+
+        if depth <= 0 {
+            return self.qsearch::<PV>(alpha, beta, ply);
+        }
+
+        self.data.nodes += 1;
+        self.data.seldepth = self.data.seldepth.max(ply);
+        self.check_limits();
+
+        if self.shared.is_stopped() {
+            return DRAW;
+        }
+
+        if !root && self.data.board.is_draw() {
+            return DRAW;
+        }
+
+        // Solved endgames are exact, unless the mate comes too late for the fifty move rule, mates past MAX_PLY become tablebase wins
+        if !root && let Some(outcome) = endgame::probe(&self.data.board) {
+            let halfmove = self.data.board.state.halfmove() as i32;
+            let mate = |plies: i32| match ply as i32 + plies < MAX_PLY as i32 {
+                true => MATE - ply as i32 - plies,
+                false => TB_WIN - ply as i32
+            };
+
+            match outcome {
+                Outcome::Win(plies) if halfmove + plies < 100 => return mate(plies),
+                Outcome::Loss(plies) if halfmove + plies < 100 => return -mate(plies),
+                Outcome::Draw => return DRAW,
+                _ => ()
+            }
+        }
+
+        // Mate distance pruning, no score here can beat a shorter mate already found
+        if !root {
+            alpha = alpha.max(-MATE + ply as i32);
+            beta = beta.min(MATE - ply as i32 - 1);
+
+            if alpha >= beta {
+                return alpha;
+            }
+        }
+
+        let in_check = self.data.board.state.checkers().is_some();
+
+        if ply >= MAX_PLY - 1 {
+            return if in_check { DRAW } else { evaluate(&self.data.board) };
+        }
+
+        // A singular search looks at the same position without the hash move, so it can't use or fill the table
+        let excluded = self.data.stack[ply].excluded;
+        let key = self.data.board.state.key();
+        let entry = self.tt.probe(key).filter(|_| excluded.is_null()).map(|entry| TTEntry { score: from_tt(entry.score, ply), ..entry });
+        let hasher = entry.map_or(Move::NULL, |entry| entry.mv);
+
+        if let Some(entry) = entry && !PV && entry.depth >= depth && is_cutoff(entry.bound, entry.score, alpha, beta) {
+            return entry.score;
+        }
+
+        let mut best_score = -INFINITE;
+        let mut max_score = INFINITE;
+
+        // Tables assume a reset halfmove clock, so they are only probed right after a capture or a pawn move
+        if !root && excluded.is_null() && self.data.board.state.halfmove() == 0 && let Some(wdl) = self.tablebase.probe_wdl(&mut self.data.board) {
+            self.data.tbhits += 1;
+
+            let (score, bound) = match wdl {
+                Wdl::Win => (TB_WIN - ply as i32, Bound::Lower),
+                Wdl::Loss => (-TB_WIN + ply as i32, Bound::Upper),
+                Wdl::CursedWin => (DRAW + 1, Bound::Exact),
+                Wdl::BlessedLoss => (DRAW - 1, Bound::Exact),
+                Wdl::Draw => (DRAW, Bound::Exact)
+            };
+
+            if is_cutoff(bound, score, alpha, beta) {
+                self.tt.store(key, TTEntry { mv: Move::NULL, score: to_tt(score, ply), eval: DRAW, depth: (depth + 6).min(MAX_PLY as i32), bound });
+
+                return score;
+            }
+
+            if PV {
+                match bound {
+                    Bound::Lower => {
+                        best_score = score;
+                        alpha = alpha.max(score);
+                    },
+                    _ => max_score = score
+                }
+            }
+        }
+
+        let eval = match in_check {
+            true => None,
+            false => Some(evaluate(&self.data.board))
+        };
+
+        self.data.stack[ply].eval = eval;
+        self.data.stack[ply + 1].killers = [Move::NULL; 2];
+
+        // Compared with the last eval of the same side, so margins can be tighter when the position gets better
+        let improving = match eval {
+            Some(eval) => [2, 4].iter().filter_map(|&offset| self.data.stack[ply.checked_sub(offset)?].eval).next().is_none_or(|previous| eval > previous),
+            None => false
+        };
+
+        if !PV && excluded.is_null() && let Some(eval) = eval {
+            // Reverse futility pruning
+            if depth <= params::rfp_depth() && eval.abs() < TB_WIN_IN_MAX_PLY && eval - (params::rfp_margin() * depth - params::rfp_improving() * improving as i32) >= beta {
+                return eval;
+            }
+
+            // Razoring
+            if depth <= params::razor_depth() && eval + params::razor_margin() * depth < alpha {
+                let score = self.qsearch::<false>(alpha, beta, ply);
+
+                if score < alpha {
+                    return score;
+                }
+            }
+
+            // Null move pruning, never without pieces where passing might be the only way to lose
+            if depth >= params::nmp_depth() && eval >= beta && beta > -TB_WIN_IN_MAX_PLY && ply >= self.data.nmp_min_ply && ply > 0 && self.data.stack[ply - 1].mv.is_some() {
+                let state = &self.data.board.state;
+                let pieces = state.colors(self.data.board.color) & !state.pieces(PieceKind::Pawn) & !state.pieces(PieceKind::King);
+
+                if pieces.is_some() {
+                    let reduction = params::nmp_base() + depth / params::nmp_divisor() + ((eval - beta) / params::nmp_eval_divisor()).min(3);
+
+                    self.data.stack[ply].mv = Move::NULL;
+                    self.data.board.make_null();
+
+                    let score = -self.negamax::<false>(-beta, -beta + 1, depth - reduction, ply + 1, !cut_node);
+
+                    self.data.board.unmake_null();
+
+                    if self.shared.is_stopped() {
+                        return DRAW;
+                    }
+
+                    if score >= beta {
+                        let score = if score >= TB_WIN_IN_MAX_PLY { beta } else { score };
+
+                        // Deep searches and a lone piece are zugzwang prone, so the cutoff is verified without null moves near the root
+                        if self.data.nmp_min_ply > 0 || (depth < params::nmp_verification_depth() && !pieces.is_only()) {
+                            return score;
+                        }
+
+                        self.data.nmp_min_ply = ply + 3 * (depth - reduction).max(0) as usize / 4;
+
+                        let verification = self.negamax::<false>(beta - 1, beta, depth - reduction, ply, false);
+
+                        self.data.nmp_min_ply = 0;
+
+                        if verification >= beta {
+                            return score;
+                        }
+                    }
+                }
+            }
+        }
+
+        let old_alpha = alpha;
+        let mut best_move = Move::NULL;
+        let mut moves = 0;
+        let mut quiets = ArrayVec::<Move, 64>::new();
+        let mut noisies = ArrayVec::<Move, 32>::new();
+        let mut picker = MovePicker::new(self.data, ply, hasher, false);
+
+        while let Some(mv) = picker.next(self.data) {
+            if mv == excluded || !self.data.board.is_legal(mv) || (root && !self.data.is_root_move(mv)) {
+                continue;
+            }
+
+            let is_quiet = self.data.board.is_quiet(mv);
+
+            // Only prune once a move has been found that doesn't lose
+            if !root && best_score > -TB_WIN_IN_MAX_PLY {
+                // Late move pruning
+                if moves >= (params::lmp_base() + depth * depth) / (2 - improving as i32) {
+                    picker.skip_quiets();
+                }
+
+                // Futility pruning
+                if let Some(eval) = eval && depth <= params::fp_depth() && eval + params::fp_base() + params::fp_margin() * depth <= alpha {
+                    picker.skip_quiets();
+                }
+
+                let threshold = match is_quiet {
+                    true => -params::see_quiet_margin() * depth,
+                    false => -params::see_noisy_margin() * depth * depth
+                };
+
+                if (is_quiet && picker.is_skipping()) || !see(&self.data.board, mv, threshold) {
+                    continue;
+                }
+            }
+
+            moves += 1;
+
+            let _ = match is_quiet {
+                true => quiets.try_push(mv),
+                false => noisies.try_push(mv)
+            };
+
+            let history = match is_quiet {
+                true => self.data.quiet_score(mv, ply),
+                false => self.data.noisy_score(mv)
+            };
+
+            // Extensions stop past twice the iteration depth so lines can't grow without bound
+            let mut extension = 0;
+            let extend = (ply as i32) < 2 * (self.data.depth + 1);
+
+            self.data.stack[ply].double_extensions = if ply > 0 { self.data.stack[ply - 1].double_extensions } else { 0 };
+
+            // Singular extensions, the hash move is searched deeper when every other move fails low against a lowered bound
+            if let Some(entry) = entry && !root && extend && mv == hasher && depth >= params::se_depth() && entry.depth >= depth - 3 && entry.bound != Bound::Upper && entry.score.abs() < TB_WIN_IN_MAX_PLY {
+                let singular_beta = entry.score - params::se_margin() * depth / 16;
+
+                self.data.stack[ply].excluded = mv;
+
+                let score = self.negamax::<false>(singular_beta - 1, singular_beta, (depth - 1) / 2, ply, cut_node);
+
+                self.data.stack[ply].excluded = Move::NULL;
+
+                if self.shared.is_stopped() {
+                    return DRAW;
+                }
+
+                if score < singular_beta {
+                    extension = 1;
+
+                    if !PV && score < singular_beta - params::se_double_margin() && self.data.stack[ply].double_extensions < params::se_double_limit() {
+                        extension = 2 + (is_quiet && score < singular_beta - params::se_triple_margin()) as i32;
+                        self.data.stack[ply].double_extensions += 1;
+                    }
+                }
+                // Multi cut, other moves beat beta too so one of them will
+                else if singular_beta >= beta {
+                    return singular_beta;
+                }
+                // Negative extensions, the hash move is unlikely to be the only good move
+                else if entry.score >= beta {
+                    extension = -2;
+                }
+                else if cut_node {
+                    extension = -1;
+                }
+            }
+
+            self.data.stack[ply].mv = mv;
+            self.data.stack[ply].piece = self.data.board.state.at(mv.from()).unwrap();
+            self.data.board.make(mv);
+
+            let nodes = self.data.nodes;
+            let gives_check = self.data.board.state.checkers().is_some();
+
+            // Check extensions
+            if gives_check && extend && extension == 0 {
+                extension = 1;
+            }
+
+            let new_depth = depth - 1 + extension;
+            let mut score;
+
+            if moves == 1 {
+                score = -self.negamax::<PV>(-beta, -alpha, new_depth, ply + 1, !PV && !cut_node);
+            }
+            else {
+                // Late move reductions, in 1024ths of a ply until rounded at the end
+                let mut reduction = 0;
+
+                if depth >= params::lmr_depth() && moves > 1 + root as i32 {
+                    reduction = self.reductions[(depth as usize).min(MAX_PLY - 1)][moves.min(63) as usize];
+                    reduction -= history * 1024 / params::lmr_history_divisor();
+                    reduction -= 1024 * PV as i32;
+                    reduction += 1024 * cut_node as i32;
+                    reduction += 1024 * !improving as i32;
+                    reduction -= 1024 * gives_check as i32;
+                }
+
+                let reduced = (new_depth - (reduction / 1024).max(0)).max(1).min(new_depth);
+
+                score = -self.negamax::<false>(-alpha - 1, -alpha, reduced, ply + 1, true);
+
+                if score > alpha && reduced < new_depth {
+                    score = -self.negamax::<false>(-alpha - 1, -alpha, new_depth, ply + 1, !cut_node);
+                }
+
+                if PV && score > alpha && score < beta {
+                    score = -self.negamax::<true>(-beta, -alpha, new_depth, ply + 1, false);
+                }
+            }
+
+            self.data.board.unmake();
+
+            if root && let Some(root) = self.data.root_moves.iter_mut().find(|root| root.mv == mv) {
+                root.nodes += self.data.nodes - nodes;
+            }
+
+            if self.shared.is_stopped() {
+                return DRAW;
+            }
+
+            if score > best_score {
+                best_score = score;
+
+                if score > alpha {
+                    alpha = score;
+                    best_move = mv;
+
+                    if PV {
+                        let child = self.data.stack[ply + 1].pv;
+
+                        self.data.stack[ply].pv.update(mv, &child);
+                    }
+
+                    if score >= beta {
+                        if is_quiet {
+                            self.data.update_quiets(ply, mv, &quiets, depth);
+                        }
+
+                        self.data.update_noisies(mv, &noisies, depth);
+
+                        break;
+                    }
+                }
+            }
+        }
+
+        // Without the excluded move there may be nothing left, which says nothing about mate
+        if moves == 0 {
+            return match (excluded.is_some(), in_check) {
+                (true, _) => alpha,
+                (false, true) => -MATE + ply as i32,
+                (false, false) => DRAW
+            };
+        }
+
+        best_score = best_score.min(max_score);
+
+        let bound = match best_score {
+            _ if best_score >= beta => Bound::Lower,
+            _ if alpha > old_alpha => Bound::Exact,
+            _ => Bound::Upper
+        };
+
+        if excluded.is_null() {
+            self.tt.store(key, TTEntry { mv: best_move, score: to_tt(best_score, ply), eval: eval.unwrap_or(DRAW), depth, bound });
+        }
+
+        best_score
+    }
+
+    // Captures and promotions only, all evasions when in check
+    fn qsearch<const PV: bool>(&mut self, mut alpha: i32, beta: i32, ply: usize) -> i32 {
+        self.data.nodes += 1;
+        self.data.seldepth = self.data.seldepth.max(ply);
+        self.check_limits();
+
+        if self.shared.is_stopped() {
+            return DRAW;
+        }
+
+        if self.data.board.is_draw() {
+            return DRAW;
+        }
+
+        let in_check = self.data.board.state.checkers().is_some();
+
+        if ply >= MAX_PLY - 1 {
+            return if in_check { DRAW } else { evaluate(&self.data.board) };
+        }
+
+        let key = self.data.board.state.key();
+        let entry = self.tt.probe(key).map(|entry| TTEntry { score: from_tt(entry.score, ply), ..entry });
+        let hasher = entry.map_or(Move::NULL, |entry| entry.mv);
+
+        if let Some(entry) = entry && !PV && is_cutoff(entry.bound, entry.score, alpha, beta) {
+            return entry.score;
+        }
+
+        let eval = match in_check {
+            true => None,
+            false => Some(evaluate(&self.data.board))
+        };
+
+        let mut best_score = -INFINITE;
+
+        if let Some(eval) = eval {
+            if eval >= beta {
+                return eval;
+            }
+
+            alpha = alpha.max(eval);
+            best_score = eval;
+        }
+
+        let old_alpha = alpha;
+        let mut best_move = Move::NULL;
+        let mut moves = 0;
+        let mut picker = MovePicker::new(self.data, ply, hasher, !in_check);
+
+        while let Some(mv) = picker.next(self.data) {
+            // Captures losing material are only worth a look to get out of check, the hash move is checked here as the picker doesn't
+            if !in_check {
+                if picker.stage() == Stage::BadNoisy {
+                    break;
+                }
+
+                if mv == hasher && !see(&self.data.board, mv, 0) {
+                    continue;
+                }
+            }
+
+            if !self.data.board.is_legal(mv) {
+                continue;
+            }
+
+            moves += 1;
+
+            self.data.stack[ply].mv = mv;
+            self.data.stack[ply].piece = self.data.board.state.at(mv.from()).unwrap();
+            self.data.board.make(mv);
+
+            let score = -self.qsearch::<PV>(-beta, -alpha, ply + 1);
+
+            self.data.board.unmake();
+
+            if self.shared.is_stopped() {
+                return DRAW;
+            }
+
+            if score > best_score {
+                best_score = score;
+
+                if score > alpha {
+                    alpha = score;
+                    best_move = mv;
+
+                    if score >= beta {
+                        break;
+                    }
+                }
+            }
+        }
+
+        if in_check && moves == 0 {
+            return -MATE + ply as i32;
+        }
+
+        let bound = match best_score {
+            _ if best_score >= beta => Bound::Lower,
+            _ if alpha > old_alpha => Bound::Exact,
+            _ => Bound::Upper
+        };
+
+        self.tt.store(key, TTEntry { mv: best_move, score: to_tt(best_score, ply), eval: eval.unwrap_or(DRAW), depth: 0, bound });
+
+        best_score
+    }
+}
+
+// Whether a bounded score is enough to return without searching
+fn is_cutoff(bound: Bound, score: i32, alpha: i32, beta: i32) -> bool {
+    match bound {
+        Bound::Exact => true,
+        Bound::Lower => score >= beta,
+        Bound::Upper => score <= alpha,
+        Bound::None => false
+    }
+}
+
+// Mate and tablebase scores count plies from the root, the table keeps them from the stored position instead
+fn to_tt(score: i32, ply: usize) -> i32 {
+    match score {
+        _ if score >= TB_WIN_IN_MAX_PLY => score + ply as i32,
+        _ if score <= -TB_WIN_IN_MAX_PLY => score - ply as i32,
+        _ => score
+    }
+}
+
+fn from_tt(score: i32, ply: usize) -> i32 {
+    match score {
+        _ if score >= TB_WIN_IN_MAX_PLY => score - ply as i32,
+        _ if score <= -TB_WIN_IN_MAX_PLY => score + ply as i32,
+        _ => score
+    }
+}
+
+// Moves to mate for mate scores, centipawns otherwise
+fn format_score(score: i32) -> String {
+    match score {
+        _ if score >= MATE_IN_MAX_PLY => format!("mate {}", (MATE - score + 1) / 2),
+        _ if score <= -MATE_IN_MAX_PLY => format!("mate -{}", (MATE + score) / 2),
+        _ => format!("cp {}", score)
+    }
+}
+
+// Base late move reduction by depth and move number, in 1024ths of a ply
+fn reductions() -> Box<[[i32; 64]; MAX_PLY]> {
+    let mut reductions = Box::new([[0; 64]; MAX_PLY]);
+
+    for (depth, row) in reductions.iter_mut().enumerate().skip(1) {
+        for (moves, reduction) in row.iter_mut().enumerate().skip(1) {
+            let base = params::lmr_base() as f64 / 100.0 + (depth as f64).ln() * (moves as f64).ln() / (params::lmr_divisor() as f64 / 100.0);
+
+            *reduction = (base * 1024.0) as i32;
+        }
+    }
+
+    reductions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{chess::board::Board, engine::endgame::Endgame};
+
+    #[test]
+    fn multipv() {
+        let tt = TT::new(1);
+        let tablebase = Tablebase::new();
+        let shared = Shared::new(1);
+        let mut data = Data::new(0, Board::startpos().unwrap());
+
+        let (mv, score) = Search::new(&mut data, &tt, &tablebase, &shared, Limits { depth: 4, multipv: 3, ..Limits::new() }).run();
+        let lines = &data.root_moves[..3];
+
+        assert_eq!((mv, score), (lines[0].mv, lines[0].score));
+        assert!(lines.windows(2).all(|pair| pair[0].score >= pair[1].score && pair[0].mv != pair[1].mv));
+        assert!(lines.iter().all(|root| root.pv.front() == root.mv && root.pv.len() >= 4));
+    }
+
+    fn search(fen: &str, depth: i32) -> (Move, i32) {
+        let tt = TT::new(1);
+        let tablebase = Tablebase::new();
+        let shared = Shared::new(1);
+        let mut data = Data::new(0, Board::from_fen(fen).unwrap());
+
+        Search::new(&mut data, &tt, &tablebase, &shared, Limits { depth, ..Limits::new() }).run()
+    }
+
+    #[test]
+    fn mates() {
+        let (mv, score) = search("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1", 3);
+
+        assert_eq!(mv.to_string(), "a1a8");
+        assert_eq!(score, MATE - 1);
+
+        let (mv, score) = search("6k1/5ppp/8/8/8/8/r4PPP/6K1 w - - 0 1", 4);
+
+        assert!(mv.is_some());
+        assert!(score < 0);
+
+        assert_eq!(search("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1", 3), (Move::NULL, DRAW));
+
+        // Mates beyond the search depth come from the built in endgame tables, once they are generated
+        endgame::table(Endgame::Krk);
+
+        let (mv, score) = search("8/8/8/8/8/8/1k6/R3K3 w - - 0 1", 2);
+
+        assert!(mv.is_some());
+        assert_eq!(score, MATE - 19);
+    }
+
+    #[test]
+    fn mate_scores() {
+        assert_eq!(format_score(MATE - 1), "mate 1");
+        assert_eq!(format_score(MATE - 4), "mate 2");
+        assert_eq!(format_score(-MATE + 2), "mate -1");
+        assert_eq!(format_score(TB_WIN - 3), "cp 30741");
+
+        for score in [MATE - 5, -MATE + 8, TB_WIN - 10, -TB_WIN + 3, 150, -20] {
+            assert_eq!(from_tt(to_tt(score, 7), 7), score);
+        }
+
+        // A mate stored at ply 3 is one ply closer when found again at ply 4
+        assert_eq!(from_tt(to_tt(MATE - 5, 3), 4), MATE - 6);
+
+        let tt = TT::new(1);
+        let tablebase = Tablebase::new();
+        let shared = Shared::new(1);
+        let mut data = Data::new(0, Board::from_fen("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1").unwrap());
+
+        Search::new(&mut data, &tt, &tablebase, &shared, Limits { mate: Some(1), ..Limits::new() }).run();
+
+        assert_eq!(data.depth, 1);
+    }
+
+    #[test]
+    fn qsearch() {
+        let tt = TT::new(1);
+        let tablebase = Tablebase::new();
+        let shared = Shared::new(1);
+        let mut data = Data::new(0, Board::from_fen("4k3/8/4p3/3p4/8/8/8/3QK3 w - - 0 1").unwrap());
+        let eval = evaluate(&data.board);
+
+        // Taking the defended pawn loses the queen, so it isn't searched at all
+        let score = Search::new(&mut data, &tt, &tablebase, &shared, Limits::new()).qsearch::<true>(-INFINITE, INFINITE, 0);
+
+        assert_eq!((score, data.nodes), (eval, 1));
+
+        // Not even when the table hands it over as the hash move
+        let mv = data.board.generate_noisy_moves().iter_moves().copied().find(|mv| mv.to_string() == "d1d5").unwrap();
+
+        tt.store(data.board.state.key(), TTEntry { mv, score: 0, eval, depth: 1, bound: Bound::Upper });
+        data.nodes = 0;
+
+        let score = Search::new(&mut data, &tt, &tablebase, &shared, Limits::new()).qsearch::<true>(-INFINITE, INFINITE, 0);
+
+        assert_eq!((score, data.nodes), (eval, 1));
+    }
+
+    // Scores far from the previous iteration fail out of the window, the re-searches end on the full window score
+    #[test]
+    fn aspiration() {
+        let depth = params::asp_depth() + 1;
+
+        for (fen, expected) in [("k7/8/1K6/6p1/6P1/8/8/7R w - - 0 1", MATE - 1), ("k7/8/1K6/6p1/6P1/8/8/7R b - - 0 1", -MATE + 2)] {
+            let mut scores = Vec::new();
+
+            for full in [false, true] {
+                let tt = TT::new(1);
+                let tablebase = Tablebase::new();
+                let shared = Shared::new(1);
+                let mut data = Data::new(0, Board::from_fen(fen).unwrap());
+                let moves: Vec<Move> = data.board.generate_moves().iter_moves().copied().filter(|&mv| data.board.is_legal(mv)).collect();
+
+                // Root moves start at 0 as if the previous iteration saw an equal position
+                data.set_root_moves(moves.into_iter());
+
+                let mut search = Search::new(&mut data, &tt, &tablebase, &shared, Limits { silent: true, ..Limits::new() });
+                let score = match full {
+                    false => search.aspiration(depth),
+                    true => search.negamax::<true>(-INFINITE, INFINITE, depth, 0, false)
+                };
+
+                assert!(data.root_moves.iter().any(|root| root.mv == data.stack[0].pv.front()));
+
+                scores.push(score);
+            }
+
+            assert_eq!(scores, [expected, expected], "{}", fen);
+        }
+    }
+
+    #[test]
+    fn singular() {
+        let tt = TT::new(1);
+        let tablebase = Tablebase::new();
+        let shared = Shared::new(1);
+        let depth = params::se_depth();
+        let find = |board: &Board, uci: &str| board.generate_moves().iter_moves().copied().find(|mv| mv.to_string() == uci).unwrap();
+
+        // Taking the queen is the only move, so it is extended twice and fails high on its own
+        let mut data = Data::new(0, Board::from_fen("rnb1kbnr/pppp1ppp/8/8/3qP3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 0 1").unwrap());
+        let capture = find(&data.board, "f3d4");
+
+        tt.store(data.board.state.key(), TTEntry { mv: capture, score: 800, eval: 0, depth: depth - 1, bound: Bound::Lower });
+
+        let score = Search::new(&mut data, &tt, &tablebase, &shared, Limits::new()).negamax::<false>(499, 500, depth, 1, false);
+
+        assert!(score >= 500);
+        assert_eq!((data.stack[1].excluded, data.stack[1].double_extensions), (Move::NULL, 1));
+
+        // Other moves beat beta as well, so the node is cut before the hash move is searched
+        let mut data = Data::new(0, Board::from_fen("4k3/8/8/8/8/8/PPP5/1K1Q4 w - - 0 1").unwrap());
+        let quiet = find(&data.board, "d1d2");
+
+        tt.store(data.board.state.key(), TTEntry { mv: quiet, score: 1000, eval: 0, depth: depth - 1, bound: Bound::Lower });
+
+        let score = Search::new(&mut data, &tt, &tablebase, &shared, Limits::new()).negamax::<true>(899, 900, depth, 1, false);
+
+        assert_eq!(score, 1000 - params::se_margin() * depth / 16);
+        assert!(data.stack[1].excluded.is_null());
+
+        // Without its only move a position is worth alpha, and the table doesn't keep that
+        let mut data = Data::new(0, Board::from_fen("k7/8/1K6/6p1/6P1/8/8/7R b - - 0 1").unwrap());
+
+        data.stack[1].excluded = find(&data.board, "a8b8");
+        tt.clear();
+
+        let score = Search::new(&mut data, &tt, &tablebase, &shared, Limits::new()).negamax::<false>(-50, -49, 4, 1, false);
+
+        assert_eq!(score, -50);
+        assert!(tt.probe(data.board.state.key()).is_none());
+    }
+
+    #[test]
+    fn reductions() {
+        let reductions = super::reductions();
+
+        assert_eq!(reductions[1][1], reductions[1][63]);
+        assert!(reductions.iter().skip(1).all(|row| row[1..].windows(2).all(|pair| pair[0] <= pair[1])));
+        assert!((1..MAX_PLY - 1).all(|depth| reductions[depth][10] <= reductions[depth + 1][10]));
+        assert!(reductions[20][40] > 3 * 1024);
+    }
+}
\ No newline at end of file
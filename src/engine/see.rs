@@ -0,0 +1,113 @@
+use crate::chess::{attack::{bishop_attacks, rook_attacks}, bitboard::Bitboard, board::Board, moves::Move, piece::PieceKind};
+
+pub const SEE_VALUES: [i32; PieceKind::COUNT] = [100, 300, 300, 500, 900, 0];
+
+// Material the side to move gets for the move when both sides keep recapturing with their least valuable attacker
+#[inline(always)]
+pub fn gain(board: &Board, mv: Move) -> i32 {
+    let captured = match board.state.at(mv.to()) {
+        Some(piece) => SEE_VALUES[piece.kind()],
+        None if mv.is_enpassant() => SEE_VALUES[PieceKind::Pawn],
+        None => 0
+    };
+
+    captured + mv.promotion_kind().map_or(0, |kind| SEE_VALUES[kind] - SEE_VALUES[PieceKind::Pawn])
+}
+
+// Whether the exchange on the target square wins at least the threshold, pins are ignored
+pub fn see(board: &Board, mv: Move, threshold: i32) -> bool {
+    if mv.is_castling() {
+        return threshold <= 0;
+    }
+
+    let state = &board.state;
+    let from = mv.from();
+    let to = mv.to();
+
+    let mut balance = gain(board, mv) - threshold;
+
+    if balance < 0 {
+        return false;
+    }
+
+    let moving = mv.promotion_kind().unwrap_or_else(|| state.at(from).unwrap().kind());
+
+    balance -= SEE_VALUES[moving];
+
+    if balance >= 0 {
+        return true;
+    }
+
+    let mut occupied = state.occupied() ^ Bitboard::from_square(from) | Bitboard::from_square(to);
+
+    if mv.is_enpassant() {
+        occupied.clear(to.enpassant());
+    }
+
+    let diagonals = state.pieces(PieceKind::Bishop) | state.pieces(PieceKind::Queen);
+    let orthogonals = state.pieces(PieceKind::Rook) | state.pieces(PieceKind::Queen);
+
+    let mut attackers = state.attackers(to, occupied) & occupied;
+    let mut color = !board.color;
+
+    loop {
+        let ours = attackers & state.colors(color);
+
+        if ours.is_empty() {
+            break;
+        }
+
+        let kind = PieceKind::all().find(|&kind| (ours & state.pieces(kind)).is_some()).unwrap();
+
+        occupied.clear((ours & state.pieces(kind)).lsb());
+
+        if matches!(kind, PieceKind::Pawn | PieceKind::Bishop | PieceKind::Queen) {
+            attackers |= bishop_attacks(to, occupied) & diagonals;
+        }
+
+        if matches!(kind, PieceKind::Rook | PieceKind::Queen) {
+            attackers |= rook_attacks(to, occupied) & orthogonals;
+        }
+
+        attackers &= occupied;
+        color = !color;
+        balance = -balance - 1 - SEE_VALUES[kind];
+
+        if balance >= 0 {
+            // The king can't recapture into a defended square
+            if kind == PieceKind::King && (attackers & state.colors(color)).is_some() {
+                color = !color;
+            }
+
+            break;
+        }
+    }
+
+    color != board.color
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chess::{moves::MoveKind, square::Square};
+
+    #[test]
+    fn exchanges() {
+        let cases = [
+            ("1k1r4/1pp4p/p7/4p3/8/P5P1/1PP4P/2K1R3 w - - 0 1", Move::new(Square::E1, Square::E5, MoveKind::Normal), 100),
+            ("1k1r3q/1ppn3p/p4b2/4p3/8/P2N2P1/1PP1R1BP/2K1Q3 w - - 0 1", Move::new(Square::D3, Square::E5, MoveKind::Normal), -200),
+            ("4k3/8/8/3p4/4P3/8/8/4K3 w - - 0 1", Move::new(Square::E4, Square::D5, MoveKind::Normal), 100),
+            ("4k3/8/2p5/3p4/4P3/8/8/4K3 w - - 0 1", Move::new(Square::E4, Square::D5, MoveKind::Normal), 0),
+            ("4k3/8/8/2r5/8/8/8/2R1K3 w - - 0 1", Move::new(Square::C1, Square::C5, MoveKind::Normal), 500),
+            ("1r2k3/P7/8/8/8/8/8/4K3 w - - 0 1", Move::new(Square::A7, Square::A8, MoveKind::PromotionQueen), -100),
+            ("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1", Move::new(Square::E5, Square::D6, MoveKind::Enpassant), 100)
+        ];
+
+        for (fen, mv, value) in cases {
+            let board = Board::from_fen(fen).unwrap();
+
+            assert!(see(&board, mv, value), "{}", fen);
+            assert!(!see(&board, mv, value + 1), "{}", fen);
+        }
+    }
+}
\ No newline at end of file
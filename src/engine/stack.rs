@@ -1,27 +1,39 @@
-use crate::{chess::moves::Move, engine::pv::{MAX_STACK, Pv}};
-
-#[derive(Debug, Clone, Copy)]
-pub struct StackEntry {
-    pub pv: Pv,
-    pub mv: Move,
-    pub eval: Option<i32>
-}
-
-#[derive(Debug, Clone)]
-pub struct Stack {
-    data: [StackEntry; MAX_STACK]
-}
-
-impl Stack {
-    pub const fn new() -> Self {
-        Self { data: () }
-    }
-}
-
-impl std::ops::Index<usize> for Stack {
-    type Output = StackEntry;
-
-    fn index(&self, index: usize) -> &Self::Output {
-        &self.data[index]
-    }
+use crate::{chess::{moves::Move, piece::Piece}, engine::pv::{MAX_STACK, Pv}};
+
+#[derive(Debug, Clone, Copy)]
+pub struct StackEntry {
+    pub pv: Pv,
+    pub mv: Move,
+    pub piece: Piece,
+    pub killers: [Move; 2],
+    pub eval: Option<i32>,
+    pub excluded: Move,
+    pub double_extensions: i32
+}
+
+#[derive(Debug, Clone)]
+pub struct Stack {
+    data: [StackEntry; MAX_STACK]
+}
+
+impl Stack {
+    pub const fn new() -> Self {
+        Self {
+            data: [StackEntry { pv: Pv::new(), mv: Move::NULL, piece: Piece::WhitePawn, killers: [Move::NULL; 2], eval: None, excluded: Move::NULL, double_extensions: 0 }; MAX_STACK]
+        }
+    }
+}
+
+impl std::ops::Index<usize> for Stack {
+    type Output = StackEntry;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.data[index]
+    }
+}
+
+impl std::ops::IndexMut<usize> for Stack {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.data[index]
+    }
 }
\ No newline at end of file
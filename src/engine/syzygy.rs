@@ -0,0 +1,1618 @@
+use std::{collections::HashMap, path::{Path, PathBuf}, sync::OnceLock};
+
+use crate::chess::{board::Board, castle::CastleKind, color::Color, moves::Move, piece::{Piece, PieceKind}};
+
+const WDL_MAGIC: [u8; 4] = [0x71, 0xe8, 0x23, 0x5d];
+const DTZ_MAGIC: [u8; 4] = [0xd7, 0x66, 0x0c, 0xa5];
+
+const MAX_PIECES: usize = 7;
+const MAX_BLOCK_SIZE: usize = 1024;
+
+// Table layout flags
+const SPLIT: u8 = 1;
+const HAS_PAWNS: u8 = 2;
+
+// Subtable flags
+const STM: u8 = 1;
+const MAPPED: u8 = 2;
+const WIN_PLIES: u8 = 4;
+const LOSS_PLIES: u8 = 8;
+const WIDE_DTZ: u8 = 16;
+const SINGLE_VALUE: u8 = 128;
+
+// Maps squares into the a1-d1-d4 triangle
+const TRIANGLE: [u64; 64] = [
+    6, 0, 1, 2, 2, 1, 0, 6,
+    0, 7, 3, 4, 4, 3, 7, 0,
+    1, 3, 8, 5, 5, 8, 3, 1,
+    2, 4, 5, 9, 9, 5, 4, 2,
+    2, 4, 5, 9, 9, 5, 4, 2,
+    1, 3, 8, 5, 5, 8, 3, 1,
+    0, 7, 3, 4, 4, 3, 7, 0,
+    6, 0, 1, 2, 2, 1, 0, 6
+];
+
+const INV_TRIANGLE: [usize; 10] = [1, 2, 3, 10, 11, 19, 0, 9, 18, 27];
+
+// Maps the b1-h1-h7 triangle to 0..=27
+const LOWER: [u64; 64] = [
+    28,  0,  1,  2,  3,  4,  5,  6,
+     0, 29,  7,  8,  9, 10, 11, 12,
+     1,  7, 30, 13, 14, 15, 16, 17,
+     2,  8, 13, 31, 18, 19, 20, 21,
+     3,  9, 14, 18, 32, 22, 23, 24,
+     4, 10, 15, 19, 22, 33, 25, 26,
+     5, 11, 16, 20, 23, 25, 34, 27,
+     6, 12, 17, 21, 24, 26, 27, 35
+];
+
+const MULT_TWIST: [u64; 64] = [
+    15, 63, 55, 47, 40, 48, 56, 12,
+    62, 11, 39, 31, 24, 32,  8, 57,
+    54, 38,  7, 23, 16,  4, 33, 49,
+    46, 30, 22,  3,  0, 17, 25, 41,
+    45, 29, 21,  2,  1, 18, 26, 42,
+    53, 37,  6, 20, 19,  5, 34, 50,
+    61, 10, 36, 28, 27, 35,  9, 58,
+    14, 60, 52, 44, 43, 51, 59, 13
+];
+
+// The a7-a5-c5 triangle
+const TEST45: u64 = 0x0001030700000000;
+
+// Unused slot
+const Z0: u64 = u64::MAX;
+
+const KK_IDX: [[u64; 64]; 10] = [
+    [
+         Z0,  Z0,  Z0,   0,   1,   2,   3,   4,
+         Z0,  Z0,  Z0,   5,   6,   7,   8,   9,
+         10,  11,  12,  13,  14,  15,  16,  17,
+         18,  19,  20,  21,  22,  23,  24,  25,
+         26,  27,  28,  29,  30,  31,  32,  33,
+         34,  35,  36,  37,  38,  39,  40,  41,
+         42,  43,  44,  45,  46,  47,  48,  49,
+         50,  51,  52,  53,  54,  55,  56,  57
+    ],
+    [
+         58,  Z0,  Z0,  Z0,  59,  60,  61,  62,
+         63,  Z0,  Z0,  Z0,  64,  65,  66,  67,
+         68,  69,  70,  71,  72,  73,  74,  75,
+         76,  77,  78,  79,  80,  81,  82,  83,
+         84,  85,  86,  87,  88,  89,  90,  91,
+         92,  93,  94,  95,  96,  97,  98,  99,
+        100, 101, 102, 103, 104, 105, 106, 107,
+        108, 109, 110, 111, 112, 113, 114, 115
+    ],
+    [
+        116, 117,  Z0,  Z0,  Z0, 118, 119, 120,
+        121, 122,  Z0,  Z0,  Z0, 123, 124, 125,
+        126, 127, 128, 129, 130, 131, 132, 133,
+        134, 135, 136, 137, 138, 139, 140, 141,
+        142, 143, 144, 145, 146, 147, 148, 149,
+        150, 151, 152, 153, 154, 155, 156, 157,
+        158, 159, 160, 161, 162, 163, 164, 165,
+        166, 167, 168, 169, 170, 171, 172, 173
+    ],
+    [
+        174,  Z0,  Z0,  Z0, 175, 176, 177, 178,
+        179,  Z0,  Z0,  Z0, 180, 181, 182, 183,
+        184,  Z0,  Z0,  Z0, 185, 186, 187, 188,
+        189, 190, 191, 192, 193, 194, 195, 196,
+        197, 198, 199, 200, 201, 202, 203, 204,
+        205, 206, 207, 208, 209, 210, 211, 212,
+        213, 214, 215, 216, 217, 218, 219, 220,
+        221, 222, 223, 224, 225, 226, 227, 228
+    ],
+    [
+        229, 230,  Z0,  Z0,  Z0, 231, 232, 233,
+        234, 235,  Z0,  Z0,  Z0, 236, 237, 238,
+        239, 240,  Z0,  Z0,  Z0, 241, 242, 243,
+        244, 245, 246, 247, 248, 249, 250, 251,
+        252, 253, 254, 255, 256, 257, 258, 259,
+        260, 261, 262, 263, 264, 265, 266, 267,
+        268, 269, 270, 271, 272, 273, 274, 275,
+        276, 277, 278, 279, 280, 281, 282, 283
+    ],
+    [
+        284, 285, 286, 287, 288, 289, 290, 291,
+        292, 293,  Z0,  Z0,  Z0, 294, 295, 296,
+        297, 298,  Z0,  Z0,  Z0, 299, 300, 301,
+        302, 303,  Z0,  Z0,  Z0, 304, 305, 306,
+        307, 308, 309, 310, 311, 312, 313, 314,
+        315, 316, 317, 318, 319, 320, 321, 322,
+        323, 324, 325, 326, 327, 328, 329, 330,
+        331, 332, 333, 334, 335, 336, 337, 338
+    ],
+    [
+         Z0,  Z0, 339, 340, 341, 342, 343, 344,
+         Z0,  Z0, 345, 346, 347, 348, 349, 350,
+         Z0,  Z0, 441, 351, 352, 353, 354, 355,
+         Z0,  Z0,  Z0, 442, 356, 357, 358, 359,
+         Z0,  Z0,  Z0,  Z0, 443, 360, 361, 362,
+         Z0,  Z0,  Z0,  Z0,  Z0, 444, 363, 364,
+         Z0,  Z0,  Z0,  Z0,  Z0,  Z0, 445, 365,
+         Z0,  Z0,  Z0,  Z0,  Z0,  Z0,  Z0, 446
+    ],
+    [
+         Z0,  Z0,  Z0, 366, 367, 368, 369, 370,
+         Z0,  Z0,  Z0, 371, 372, 373, 374, 375,
+         Z0,  Z0,  Z0, 376, 377, 378, 379, 380,
+         Z0,  Z0,  Z0, 447, 381, 382, 383, 384,
+         Z0,  Z0,  Z0,  Z0, 448, 385, 386, 387,
+         Z0,  Z0,  Z0,  Z0,  Z0, 449, 388, 389,
+         Z0,  Z0,  Z0,  Z0,  Z0,  Z0, 450, 390,
+         Z0,  Z0,  Z0,  Z0,  Z0,  Z0,  Z0, 451
+    ],
+    [
+        452, 391, 392, 393, 394, 395, 396, 397,
+         Z0,  Z0,  Z0,  Z0, 398, 399, 400, 401,
+         Z0,  Z0,  Z0,  Z0, 402, 403, 404, 405,
+         Z0,  Z0,  Z0,  Z0, 406, 407, 408, 409,
+         Z0,  Z0,  Z0,  Z0, 453, 410, 411, 412,
+         Z0,  Z0,  Z0,  Z0,  Z0, 454, 413, 414,
+         Z0,  Z0,  Z0,  Z0,  Z0,  Z0, 455, 415,
+         Z0,  Z0,  Z0,  Z0,  Z0,  Z0,  Z0, 456
+    ],
+    [
+        457, 416, 417, 418, 419, 420, 421, 422,
+         Z0, 458, 423, 424, 425, 426, 427, 428,
+         Z0,  Z0,  Z0,  Z0,  Z0, 429, 430, 431,
+         Z0,  Z0,  Z0,  Z0,  Z0, 432, 433, 434,
+         Z0,  Z0,  Z0,  Z0,  Z0, 435, 436, 437,
+         Z0,  Z0,  Z0,  Z0,  Z0, 459, 438, 439,
+         Z0,  Z0,  Z0,  Z0,  Z0,  Z0, 460, 440,
+         Z0,  Z0,  Z0,  Z0,  Z0,  Z0,  Z0, 461
+    ]
+];
+
+const PP_IDX: [[u64; 64]; 10] = [
+    [
+          0,  Z0,   1,   2,   3,   4,   5,   6,
+          7,   8,   9,  10,  11,  12,  13,  14,
+         15,  16,  17,  18,  19,  20,  21,  22,
+         23,  24,  25,  26,  27,  28,  29,  30,
+         31,  32,  33,  34,  35,  36,  37,  38,
+         39,  40,  41,  42,  43,  44,  45,  46,
+         Z0,  47,  48,  49,  50,  51,  52,  53,
+         54,  55,  56,  57,  58,  59,  60,  61
+    ],
+    [
+         62,  Z0,  Z0,  63,  64,  65,  Z0,  66,
+         Z0,  67,  68,  69,  70,  71,  72,  Z0,
+         73,  74,  75,  76,  77,  78,  79,  80,
+         81,  82,  83,  84,  85,  86,  87,  88,
+         89,  90,  91,  92,  93,  94,  95,  96,
+         Z0,  97,  98,  99, 100, 101, 102, 103,
+         Z0, 104, 105, 106, 107, 108, 109,  Z0,
+        110,  Z0, 111, 112, 113, 114,  Z0, 115
+    ],
+    [
+        116,  Z0,  Z0,  Z0, 117,  Z0,  Z0, 118,
+         Z0, 119, 120, 121, 122, 123, 124,  Z0,
+         Z0, 125, 126, 127, 128, 129, 130,  Z0,
+        131, 132, 133, 134, 135, 136, 137, 138,
+         Z0, 139, 140, 141, 142, 143, 144, 145,
+         Z0, 146, 147, 148, 149, 150, 151,  Z0,
+         Z0, 152, 153, 154, 155, 156, 157,  Z0,
+        158,  Z0,  Z0, 159, 160,  Z0,  Z0, 161
+    ],
+    [
+        162,  Z0,  Z0,  Z0,  Z0,  Z0,  Z0, 163,
+         Z0, 164,  Z0, 165, 166, 167, 168,  Z0,
+         Z0, 169, 170, 171, 172, 173, 174,  Z0,
+         Z0, 175, 176, 177, 178, 179, 180,  Z0,
+         Z0, 181, 182, 183, 184, 185, 186,  Z0,
+         Z0,  Z0, 187, 188, 189, 190, 191,  Z0,
+         Z0, 192, 193, 194, 195, 196, 197,  Z0,
+        198,  Z0,  Z0,  Z0,  Z0,  Z0,  Z0, 199
+    ],
+    [
+        200,  Z0,  Z0,  Z0,  Z0,  Z0,  Z0, 201,
+         Z0, 202,  Z0,  Z0, 203,  Z0, 204,  Z0,
+         Z0,  Z0, 205, 206, 207, 208,  Z0,  Z0,
+         Z0, 209, 210, 211, 212, 213, 214,  Z0,
+         Z0,  Z0, 215, 216, 217, 218, 219,  Z0,
+         Z0,  Z0, 220, 221, 222, 223,  Z0,  Z0,
+         Z0, 224,  Z0, 225, 226,  Z0, 227,  Z0,
+        228,  Z0,  Z0,  Z0,  Z0,  Z0,  Z0, 229
+    ],
+    [
+        230,  Z0,  Z0,  Z0,  Z0,  Z0,  Z0, 231,
+         Z0, 232,  Z0,  Z0,  Z0,  Z0, 233,  Z0,
+         Z0,  Z0, 234,  Z0, 235, 236,  Z0,  Z0,
+         Z0,  Z0, 237, 238, 239, 240,  Z0,  Z0,
+         Z0,  Z0,  Z0, 241, 242, 243,  Z0,  Z0,
+         Z0,  Z0, 244, 245, 246, 247,  Z0,  Z0,
+         Z0, 248,  Z0,  Z0,  Z0,  Z0, 249,  Z0,
+        250,  Z0,  Z0,  Z0,  Z0,  Z0,  Z0, 251
+    ],
+    [
+         Z0,  Z0,  Z0,  Z0,  Z0,  Z0,  Z0, 259,
+         Z0, 252,  Z0,  Z0,  Z0,  Z0, 260,  Z0,
+         Z0,  Z0, 253,  Z0,  Z0, 261,  Z0,  Z0,
+         Z0,  Z0,  Z0, 254, 262,  Z0,  Z0,  Z0,
+         Z0,  Z0,  Z0,  Z0, 255,  Z0,  Z0,  Z0,
+         Z0,  Z0,  Z0,  Z0,  Z0, 256,  Z0,  Z0,
+         Z0,  Z0,  Z0,  Z0,  Z0,  Z0, 257,  Z0,
+         Z0,  Z0,  Z0,  Z0,  Z0,  Z0,  Z0, 258
+    ],
+    [
+         Z0,  Z0,  Z0,  Z0,  Z0,  Z0,  Z0,  Z0,
+         Z0,  Z0,  Z0,  Z0,  Z0,  Z0, 268,  Z0,
+         Z0,  Z0, 263,  Z0,  Z0, 269,  Z0,  Z0,
+         Z0,  Z0,  Z0, 264, 270,  Z0,  Z0,  Z0,
+         Z0,  Z0,  Z0,  Z0, 265,  Z0,  Z0,  Z0,
+         Z0,  Z0,  Z0,  Z0,  Z0, 266,  Z0,  Z0,
+         Z0,  Z0,  Z0,  Z0,  Z0,  Z0, 267,  Z0,
+         Z0,  Z0,  Z0,  Z0,  Z0,  Z0,  Z0,  Z0
+    ],
+    [
+         Z0,  Z0,  Z0,  Z0,  Z0,  Z0,  Z0,  Z0,
+         Z0,  Z0,  Z0,  Z0,  Z0,  Z0,  Z0,  Z0,
+         Z0,  Z0,  Z0,  Z0,  Z0, 274,  Z0,  Z0,
+         Z0,  Z0,  Z0, 271, 275,  Z0,  Z0,  Z0,
+         Z0,  Z0,  Z0,  Z0, 272,  Z0,  Z0,  Z0,
+         Z0,  Z0,  Z0,  Z0,  Z0, 273,  Z0,  Z0,
+         Z0,  Z0,  Z0,  Z0,  Z0,  Z0,  Z0,  Z0,
+         Z0,  Z0,  Z0,  Z0,  Z0,  Z0,  Z0,  Z0
+    ],
+    [
+         Z0,  Z0,  Z0,  Z0,  Z0,  Z0,  Z0,  Z0,
+         Z0,  Z0,  Z0,  Z0,  Z0,  Z0,  Z0,  Z0,
+         Z0,  Z0,  Z0,  Z0,  Z0,  Z0,  Z0,  Z0,
+         Z0,  Z0,  Z0,  Z0, 277,  Z0,  Z0,  Z0,
+         Z0,  Z0,  Z0,  Z0, 276,  Z0,  Z0,  Z0,
+         Z0,  Z0,  Z0,  Z0,  Z0,  Z0,  Z0,  Z0,
+         Z0,  Z0,  Z0,  Z0,  Z0,  Z0,  Z0,  Z0,
+         Z0,  Z0,  Z0,  Z0,  Z0,  Z0,  Z0,  Z0
+    ]
+];
+struct Consts {
+    mult_idx: [[u64; 10]; 5],
+    mult_factor: [u64; 5],
+    map_pawns: [u64; 64],
+    lead_pawn_idx: [[u64; 64]; 6],
+    lead_pawns_size: [[u64; 4]; 6]
+}
+
+static CONSTS: Consts = {
+    let mut consts = Consts {
+        mult_idx: [[0; 10]; 5],
+        mult_factor: [0; 5],
+        map_pawns: [0; 64],
+        lead_pawn_idx: [[0; 64]; 6],
+        lead_pawns_size: [[0; 4]; 6]
+    };
+
+    // Identical pieces sharing the leading group
+    let mut i = 0;
+
+    while i < 5 {
+        let mut sum = 0;
+        let mut j = 0;
+
+        while j < 10 {
+            consts.mult_idx[i][j] = sum;
+            sum += if i == 0 { 1 } else { binomial(MULT_TWIST[INV_TRIANGLE[j]], i as u64) };
+
+            j += 1;
+        }
+
+        consts.mult_factor[i] = sum;
+
+        i += 1;
+    }
+
+    // Leading pawns
+    let mut available = 48;
+    let mut count = 1;
+
+    while count <= 5 {
+        let mut file = 0;
+
+        while file < 4 {
+            let mut index = 0;
+            let mut rank = 1;
+
+            while rank < 7 {
+                let square = file + 8 * rank;
+
+                if count == 1 {
+                    available -= 1;
+                    consts.map_pawns[square] = available;
+                    available -= 1;
+                    consts.map_pawns[square ^ 7] = available;
+                }
+
+                consts.lead_pawn_idx[count][square] = index;
+                index += binomial(consts.map_pawns[square], count as u64 - 1);
+
+                rank += 1;
+            }
+
+            consts.lead_pawns_size[count][file] = index;
+
+            file += 1;
+        }
+
+        count += 1;
+    }
+
+    consts
+};
+
+const fn binomial(mut n: u64, k: u64) -> u64 {
+    if k > n {
+        return 0;
+    }
+
+    if k > n - k {
+        return binomial(n, n - k);
+    }
+
+    let mut result = 1;
+    let mut d = 1;
+
+    while d <= k {
+        result = result * n / d;
+        n -= 1;
+        d += 1;
+    }
+
+    result
+}
+
+#[inline(always)]
+const fn file_of(square: usize) -> usize {
+    square & 7
+}
+
+#[inline(always)]
+const fn rank_of(square: usize) -> usize {
+    square >> 3
+}
+
+#[inline(always)]
+const fn flip_diagonal(square: usize) -> usize {
+    ((square >> 3) | (square << 3)) & 63
+}
+
+#[inline(always)]
+const fn is_offdiagonal(square: usize) -> bool {
+    file_of(square) != rank_of(square)
+}
+
+// Outcome of a position for the side to move, cursed wins and blessed losses are drawn by the 50 move rule
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Wdl {
+    Loss,
+    BlessedLoss,
+    Draw,
+    CursedWin,
+    Win
+}
+
+impl Wdl {
+    const fn from_raw(value: u16) -> Option<Self> {
+        match value {
+            0 => Some(Self::Loss),
+            1 => Some(Self::BlessedLoss),
+            2 => Some(Self::Draw),
+            3 => Some(Self::CursedWin),
+            4 => Some(Self::Win),
+            _ => None
+        }
+    }
+
+    // The dtz of a position where the best move zeroes the halfmove clock
+    const fn before_zeroing(self) -> i32 {
+        match self {
+            Self::Loss => -1,
+            Self::BlessedLoss => -101,
+            Self::Draw => 0,
+            Self::CursedWin => 101,
+            Self::Win => 1
+        }
+    }
+
+    const fn signum(self) -> i32 {
+        match self {
+            Self::Loss | Self::BlessedLoss => -1,
+            Self::Draw => 0,
+            Self::CursedWin | Self::Win => 1
+        }
+    }
+}
+
+impl std::ops::Neg for Wdl {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        match self {
+            Self::Loss => Self::Win,
+            Self::BlessedLoss => Self::CursedWin,
+            Self::Draw => Self::Draw,
+            Self::CursedWin => Self::BlessedLoss,
+            Self::Win => Self::Loss
+        }
+    }
+}
+
+// Increases the distance of a non zero dtz by some plies
+const fn add_plies(dtz: i32, plies: i32) -> i32 {
+    match dtz {
+        0 => 0,
+        _ if dtz > 0 => dtz + plies,
+        _ => dtz - plies
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+struct Material {
+    counts: [[u8; PieceKind::COUNT]; Color::COUNT]
+}
+
+impl Material {
+    fn from_pieces(pieces: &[Piece]) -> Self {
+        let mut counts = [[0; PieceKind::COUNT]; Color::COUNT];
+
+        for piece in pieces {
+            counts[piece.color()][piece.kind()] += 1;
+        }
+
+        Self { counts }
+    }
+
+    fn from_board(board: &Board) -> Self {
+        let mut counts = [[0; PieceKind::COUNT]; Color::COUNT];
+
+        for color in [Color::White, Color::Black] {
+            for kind in PieceKind::all() {
+                counts[color][kind] = (board.state.pieces(kind) & board.state.colors(color)).count() as u8;
+            }
+        }
+
+        Self { counts }
+    }
+
+    // Parses table names such as "KRPvKR"
+    fn from_name(name: &str) -> Option<Self> {
+        let (white, black) = name.split_once('v')?;
+        let mut counts = [[0; PieceKind::COUNT]; Color::COUNT];
+
+        for (color, side) in [(Color::White, white), (Color::Black, black)] {
+            for c in side.chars() {
+                counts[color][Piece::from_char(c)?.kind()] += 1;
+            }
+        }
+
+        let material = Self { counts };
+
+        match material.side_count(Color::White) >= 1 && material.side_count(Color::Black) >= 1 && material.count() <= MAX_PIECES {
+            true => Some(material),
+            false => None
+        }
+    }
+
+    fn flipped(self) -> Self {
+        Self { counts: [self.counts[1], self.counts[0]] }
+    }
+
+    fn side_count(self, color: Color) -> usize {
+        self.counts[color].iter().map(|&count| count as usize).sum()
+    }
+
+    fn count(self) -> usize {
+        self.side_count(Color::White) + self.side_count(Color::Black)
+    }
+
+    fn has_pawns(self) -> bool {
+        self.counts.iter().any(|side| side[PieceKind::Pawn] > 0)
+    }
+
+    fn is_symmetric(self) -> bool {
+        self.counts[0] == self.counts[1]
+    }
+
+    fn unique_pieces(self) -> u8 {
+        self.counts.iter().flatten().filter(|&&count| count == 1).count() as u8
+    }
+
+    fn min_like_man(self) -> u8 {
+        self.counts.iter().flatten().copied().filter(|&count| count >= 2).min().unwrap_or(0)
+    }
+}
+
+// How the pieces of one subtable are grouped and weighted in the index
+#[derive(Debug, Clone)]
+struct GroupData {
+    pieces: Vec<Piece>,
+    lens: Vec<usize>,
+    factors: Vec<u64>
+}
+
+impl GroupData {
+    fn new(pieces: Vec<Piece>, order: [u8; 2], file: usize) -> Self {
+        let material = Material::from_pieces(&pieces);
+
+        // Pawnless tables lead with three unique pieces, else the two kings, else the smallest group of identical pieces
+        let first = match material.has_pawns() {
+            true => 0,
+            false if material.unique_pieces() >= 3 => 3,
+            false if material.unique_pieces() == 2 => 2,
+            false => material.min_like_man() as usize
+        };
+
+        let mut lens = Vec::new();
+
+        if first > 0 {
+            lens.push(first);
+        }
+
+        lens.extend(pieces[first..].chunk_by(|a, b| a == b).map(<[Piece]>::len));
+
+        let pp = material.counts[Color::White][PieceKind::Pawn] > 0 && material.counts[Color::Black][PieceKind::Pawn] > 0;
+        let mut factors = vec![0; lens.len() + 1];
+        let mut free = 64 - lens[0] - if pp { lens[1] } else { 0 };
+        let mut next = if pp { 2 } else { 1 };
+        let mut index = 1;
+        let mut k = 0;
+
+        while next < lens.len() || k == order[0] || k == order[1] {
+            if k == order[0] {
+                factors[0] = index;
+
+                index *= match material.has_pawns() {
+                    true => CONSTS.lead_pawns_size[lens[0]][file],
+                    false if material.unique_pieces() >= 3 => 31332,
+                    false if material.unique_pieces() == 2 => 462,
+                    false if material.min_like_man() == 2 => 278,
+                    false => CONSTS.mult_factor[material.min_like_man() as usize - 1]
+                };
+            }
+            else if k == order[1] {
+                factors[1] = index;
+                index *= binomial(48 - lens[0] as u64, lens[1] as u64);
+            }
+            else {
+                factors[next] = index;
+                index *= binomial(free as u64, lens[next] as u64);
+                free -= lens[next];
+                next += 1;
+            }
+
+            k += 1;
+        }
+
+        factors[lens.len()] = index;
+
+        Self { pieces, lens, factors }
+    }
+}
+
+// Remaps stored dtz values, one list per decisive outcome
+#[derive(Debug, Clone, Copy)]
+struct DtzMap {
+    wide: bool,
+    offset: usize,
+    by_wdl: [usize; 4]
+}
+
+// Huffman decoding data of one subtable
+#[derive(Debug, Clone)]
+struct PairsData {
+    flags: u8,
+    groups: GroupData,
+    block_size: usize,
+    span: u64,
+    blocks: usize,
+    btree: usize,
+    min_symlen: u8,
+    lowest_sym: usize,
+    base: Vec<u64>,
+    symlen: Vec<u8>,
+    sparse_index: usize,
+    sparse_index_size: usize,
+    block_lengths: usize,
+    block_lengths_size: usize,
+    data: usize,
+    dtz_map: Option<DtzMap>
+}
+
+#[derive(Debug)]
+struct Table {
+    data: Vec<u8>,
+    dtz: bool,
+    material: Material,
+    files: Vec<Vec<PairsData>>
+}
+
+impl Table {
+    fn u8_at(&self, offset: usize) -> Option<u8> {
+        self.data.get(offset).copied()
+    }
+
+    fn u16_at(&self, offset: usize) -> Option<u16> {
+        Some(u16::from_le_bytes(self.data.get(offset..offset + 2)?.try_into().ok()?))
+    }
+
+    fn u32_at(&self, offset: usize) -> Option<u32> {
+        Some(u32::from_le_bytes(self.data.get(offset..offset + 4)?.try_into().ok()?))
+    }
+
+    // Huffman tree nodes are packed into 3 bytes
+    fn node_at(&self, offset: usize) -> Option<(u16, u16)> {
+        let bytes = self.data.get(offset..offset + 3)?;
+        let left = ((bytes[1] as u16 & 0xf) << 8) | bytes[0] as u16;
+        let right = ((bytes[2] as u16) << 4) | (bytes[1] as u16 >> 4);
+
+        Some((left, right))
+    }
+
+    fn new(data: Vec<u8>, material: Material, dtz: bool) -> Option<Self> {
+        let mut table = Self { data, dtz, material, files: Vec::new() };
+
+        let magic = if dtz { DTZ_MAGIC } else { WDL_MAGIC };
+
+        if table.data.get(0..4)? != magic || table.data.len() % 64 != 16 {
+            return None;
+        }
+
+        let layout = table.u8_at(4)?;
+
+        if (layout & HAS_PAWNS != 0) != material.has_pawns() || (layout & SPLIT != 0) == material.is_symmetric() {
+            return None;
+        }
+
+        let pp = material.counts[Color::White][PieceKind::Pawn] > 0 && material.counts[Color::Black][PieceKind::Pawn] > 0;
+        let files = if material.has_pawns() { 4 } else { 1 };
+        let sides = if !dtz && !material.is_symmetric() { 2 } else { 1 };
+
+        let mut offset = 5;
+        let mut groups = Vec::new();
+
+        for file in 0..files {
+            let first = table.u8_at(offset)?;
+            let second = if pp { table.u8_at(offset + 1)? } else { 0xff };
+            let order = [[first & 0xf, second & 0xf], [first >> 4, second >> 4]];
+
+            offset += 1 + pp as usize;
+
+            let mut file_groups = Vec::new();
+
+            for (side, color) in [Color::White, Color::Black].into_iter().take(sides).enumerate() {
+                let pieces = table.pieces_at(offset, material.count(), color)?;
+                let key = Material::from_pieces(&pieces);
+
+                if key != material && key.flipped() != material {
+                    return None;
+                }
+
+                file_groups.push(GroupData::new(pieces, order[side], file));
+            }
+
+            offset += material.count();
+            groups.push(file_groups);
+        }
+
+        offset += offset & 1;
+
+        // The leading piece of pawn tables must be a pawn
+        if (groups[0][0].pieces[0].kind() == PieceKind::Pawn) != material.has_pawns() {
+            return None;
+        }
+
+        for file_groups in groups {
+            let mut file = Vec::new();
+
+            for group in file_groups {
+                let (pairs, next) = table.pairs_at(offset, group)?;
+
+                file.push(pairs);
+                offset = next;
+            }
+
+            table.files.push(file);
+        }
+
+        if dtz {
+            let map = offset;
+
+            for file in 0..table.files.len() {
+                if table.files[file][0].flags & MAPPED == 0 {
+                    continue;
+                }
+
+                let wide = table.files[file][0].flags & WIDE_DTZ != 0;
+                let mut by_wdl = [0; 4];
+
+                for index in by_wdl.iter_mut() {
+                    match wide {
+                        true => {
+                            *index = (offset - map + 2) / 2;
+                            offset += table.u16_at(offset)? as usize * 2 + 2;
+                        },
+                        false => {
+                            *index = offset - map + 1;
+                            offset += table.u8_at(offset)? as usize + 1;
+                        }
+                    }
+                }
+
+                table.files[file][0].dtz_map = Some(DtzMap { wide, offset: map, by_wdl });
+            }
+
+            offset += offset & 1;
+        }
+
+        for pairs in table.files.iter_mut().flatten() {
+            pairs.sparse_index = offset;
+            offset += pairs.sparse_index_size * 6;
+        }
+
+        for pairs in table.files.iter_mut().flatten() {
+            pairs.block_lengths = offset;
+            offset += pairs.block_lengths_size * 2;
+        }
+
+        // Compressed data is 64 byte aligned
+        for pairs in table.files.iter_mut().flatten() {
+            offset = (offset + 0x3f) & !0x3f;
+            pairs.data = offset;
+            offset += pairs.blocks * pairs.block_size;
+        }
+
+        Some(table)
+    }
+
+    // The header stores one nibble per piece, the low one for white's point of view
+    fn pieces_at(&self, offset: usize, count: usize, color: Color) -> Option<Vec<Piece>> {
+        let mut pieces = Vec::with_capacity(count);
+
+        for byte in self.data.get(offset..offset + count)? {
+            let nibble = match color {
+                Color::White => byte & 0xf,
+                Color::Black => byte >> 4
+            };
+
+            let kind = match nibble & 7 {
+                kind @ 1..=6 => PieceKind::from_raw(kind - 1),
+                _ => return None
+            };
+
+            pieces.push(Piece::new(kind, if nibble & 8 == 0 { Color::White } else { Color::Black }));
+        }
+
+        Some(pieces)
+    }
+
+    fn pairs_at(&self, offset: usize, groups: GroupData) -> Option<(PairsData, usize)> {
+        let flags = self.u8_at(offset)?;
+
+        let mut pairs = PairsData {
+            flags,
+            groups,
+            block_size: 0,
+            span: 0,
+            blocks: 0,
+            btree: 0,
+            min_symlen: 0,
+            lowest_sym: 0,
+            base: Vec::new(),
+            symlen: Vec::new(),
+            sparse_index: 0,
+            sparse_index_size: 0,
+            block_lengths: 0,
+            block_lengths_size: 0,
+            data: 0,
+            dtz_map: None
+        };
+
+        // Single valued tables keep the value in place of the minimum symbol length
+        if flags & SINGLE_VALUE != 0 {
+            pairs.min_symlen = if self.dtz { 0 } else { self.u8_at(offset + 1)? };
+
+            return Some((pairs, offset + 2));
+        }
+
+        let size = pairs.groups.factors[pairs.groups.lens.len()];
+        let block_shift = self.u8_at(offset + 1)? as u32;
+        let span_shift = self.u8_at(offset + 2)? as u32;
+
+        if block_shift > 10 || span_shift > 31 {
+            return None;
+        }
+
+        pairs.block_size = 1 << block_shift;
+        pairs.span = 1 << span_shift;
+        pairs.sparse_index_size = size.div_ceil(pairs.span) as usize;
+        pairs.blocks = self.u32_at(offset + 4)? as usize;
+        pairs.block_lengths_size = pairs.blocks + self.u8_at(offset + 3)? as usize;
+
+        let max_symlen = self.u8_at(offset + 8)?;
+        let min_symlen = self.u8_at(offset + 9)?;
+
+        if max_symlen > 32 || min_symlen > max_symlen {
+            return None;
+        }
+
+        let h = (max_symlen - min_symlen + 1) as usize;
+
+        pairs.min_symlen = min_symlen;
+        pairs.lowest_sym = offset + 10;
+        pairs.base = vec![0; h];
+
+        for i in (0..h - 1).rev() {
+            let lowest = pairs.lowest_sym + i * 2;
+
+            pairs.base[i] = (pairs.base[i + 1] + self.u16_at(lowest)? as u64).checked_sub(self.u16_at(lowest + 2)? as u64)? / 2;
+        }
+
+        for (i, base) in pairs.base.iter_mut().enumerate() {
+            *base = base.checked_shl(64 - (min_symlen as u32 + i as u32))?;
+        }
+
+        let offset = offset + 10 + h * 2;
+        let symbols = self.u16_at(offset)? as usize;
+
+        pairs.btree = offset + 2;
+        pairs.symlen = vec![0; symbols];
+
+        let mut visited = vec![false; symbols];
+
+        for symbol in 0..symbols {
+            self.read_symlen(&mut pairs, &mut visited, symbol, 16)?;
+        }
+
+        let next = pairs.btree + symbols * 3 + (symbols & 1);
+
+        Some((pairs, next))
+    }
+
+    fn read_symlen(&self, pairs: &mut PairsData, visited: &mut [bool], symbol: usize, depth: u8) -> Option<()> {
+        if *visited.get(symbol)? {
+            return Some(());
+        }
+
+        let (left, right) = self.node_at(pairs.btree + 3 * symbol)?;
+
+        if right == 0xfff {
+            pairs.symlen[symbol] = 0;
+        }
+        else {
+            let depth = depth.checked_sub(1)?;
+
+            self.read_symlen(pairs, visited, left as usize, depth)?;
+            self.read_symlen(pairs, visited, right as usize, depth)?;
+
+            pairs.symlen[symbol] = pairs.symlen[left as usize].checked_add(pairs.symlen[right as usize])?.checked_add(1)?;
+        }
+
+        visited[symbol] = true;
+
+        Some(())
+    }
+
+    // Finds the block holding the index through the sparse index, then walks the Huffman symbols of that block
+    fn decompress(&self, pairs: &PairsData, index: u64) -> Option<u16> {
+        if pairs.flags & SINGLE_VALUE != 0 {
+            return Some(pairs.min_symlen as u16);
+        }
+
+        let main = (index / pairs.span) as usize;
+        let mut block = self.u32_at(pairs.sparse_index + 6 * main)? as usize;
+        let mut literal = (index % pairs.span) as i64 - (pairs.span / 2) as i64 + self.u16_at(pairs.sparse_index + 6 * main + 4)? as i64;
+
+        while literal < 0 {
+            block = block.checked_sub(1)?;
+            literal += self.u16_at(pairs.block_lengths + block * 2)? as i64 + 1;
+        }
+
+        loop {
+            let length = self.u16_at(pairs.block_lengths + block * 2)? as i64 + 1;
+
+            if literal < length {
+                break;
+            }
+
+            literal -= length;
+            block += 1;
+        }
+
+        // Copy the block with 4 extra bytes so refilling never reads past the end
+        let mut buffer = [0u8; MAX_BLOCK_SIZE + 8];
+        let start = pairs.data + block * pairs.block_size;
+        let end = (start + pairs.block_size + 4).min(self.data.len());
+
+        buffer[..end.checked_sub(start)?].copy_from_slice(self.data.get(start..end)?);
+
+        let mut cursor = 8;
+        let mut bits = u64::from_be_bytes(buffer[0..8].try_into().unwrap());
+        let mut size = 64;
+
+        let mut symbol;
+
+        loop {
+            let mut length = 0;
+
+            while bits < *pairs.base.get(length)? {
+                length += 1;
+            }
+
+            symbol = ((bits - pairs.base[length]) >> (64 - length - pairs.min_symlen as usize)) as usize;
+            symbol += self.u16_at(pairs.lowest_sym + 2 * length)? as usize;
+
+            let count = *pairs.symlen.get(symbol)? as i64 + 1;
+
+            if literal < count {
+                break;
+            }
+
+            literal -= count;
+            length += pairs.min_symlen as usize;
+            bits <<= length;
+            size -= length;
+
+            if size <= 32 {
+                size += 32;
+                bits |= (u32::from_be_bytes(buffer.get(cursor..cursor + 4)?.try_into().unwrap()) as u64) << (64 - size);
+                cursor += 4;
+            }
+        }
+
+        // Expand the symbol down to a single value
+        while *pairs.symlen.get(symbol)? != 0 {
+            let (left, right) = self.node_at(pairs.btree + 3 * symbol)?;
+            let count = *pairs.symlen.get(left as usize)? as i64 + 1;
+
+            if literal < count {
+                symbol = left as usize;
+            }
+            else {
+                literal -= count;
+                symbol = right as usize;
+            }
+        }
+
+        match self.dtz {
+            true => Some(self.u16_at(pairs.btree + 3 * symbol)? & 0xfff),
+            false => self.u8_at(pairs.btree + 3 * symbol).map(|value| value as u16)
+        }
+    }
+
+    // Computes the subtable and the index of a position, dtz tables return none when only the other side to move is stored
+    fn encode(&self, board: &Board) -> Option<(&PairsData, u64)> {
+        let key = Material::from_board(board);
+        let material = self.material_of_first();
+
+        let flip = (material.is_symmetric() && board.color == Color::Black) || key != material;
+        let bside = (board.color == Color::Black) ^ flip;
+        let normalize = |square: usize| if flip { square ^ 56 } else { square };
+
+        let mut squares = Vec::with_capacity(MAX_PIECES);
+        let mut used = 0u64;
+
+        // Pawn tables have one subtable for each file of the leading pawn
+        let file = match material.has_pawns() {
+            true => {
+                let lead = self.files[0][0].groups.pieces[0];
+                let color = if flip { !lead.color() } else { lead.color() };
+                let pawns = board.state.pieces(PieceKind::Pawn) & board.state.colors(color);
+
+                used |= pawns.value();
+                squares.extend(pawns.map(|square| normalize(square.index())));
+
+                for i in 1..squares.len() {
+                    if CONSTS.map_pawns[squares[0]] < CONSTS.map_pawns[squares[i]] {
+                        squares.swap(0, i);
+                    }
+                }
+
+                let file = file_of(squares[0]);
+
+                if file >= 4 { 7 - file } else { file }
+            },
+            false => 0
+        };
+
+        let sides = &self.files[file];
+        let pairs = &sides[if bside { sides.len() - 1 } else { 0 }];
+
+        if self.dtz && (pairs.flags & STM != 0) != bside && (!material.is_symmetric() || material.has_pawns()) {
+            return None;
+        }
+
+        let lead_pawns = squares.len();
+
+        for piece in pairs.groups.pieces.iter().skip(lead_pawns) {
+            let color = if flip { !piece.color() } else { piece.color() };
+            let candidates = (board.state.pieces(piece.kind()) & board.state.colors(color)).value() & !used;
+            let square = candidates.trailing_zeros() as usize;
+
+            squares.push(normalize(square));
+            used |= 1 << square;
+        }
+
+        if file_of(squares[0]) >= 4 {
+            squares.iter_mut().for_each(|square| *square ^= 7);
+        }
+
+        let lens = &pairs.groups.lens;
+
+        let mut index = match material.has_pawns() {
+            true => {
+                let mut index = CONSTS.lead_pawn_idx[lead_pawns][squares[0]];
+
+                squares[1..lead_pawns].sort_unstable_by_key(|&square| CONSTS.map_pawns[square]);
+
+                for (i, &square) in squares.iter().enumerate().take(lead_pawns).skip(1) {
+                    index += binomial(CONSTS.map_pawns[square], i as u64);
+                }
+
+                index
+            },
+            false => self.encode_pieces(&mut squares, lens[0], material)
+        };
+
+        index *= pairs.groups.factors[0];
+
+        // The remaining groups are encoded as combinations of the squares left free
+        let mut remaining_pawns = material.counts[Color::White][PieceKind::Pawn] > 0 && material.counts[Color::Black][PieceKind::Pawn] > 0;
+        let mut start = lens[0];
+
+        for (next, &len) in lens.iter().enumerate().skip(1) {
+            let (previous, group) = squares.split_at_mut(start);
+            let group = &mut group[..len];
+
+            group.sort_unstable();
+
+            let mut n = 0;
+
+            for (i, &square) in group.iter().enumerate() {
+                let adjust = previous.iter().filter(|&&other| square > other).count() as u64;
+
+                n += binomial(square as u64 - adjust - if remaining_pawns { 8 } else { 0 }, i as u64 + 1);
+            }
+
+            remaining_pawns = false;
+            index += n * pairs.groups.factors[next];
+            start += len;
+        }
+
+        Some((pairs, index))
+    }
+
+    // Index of the leading group of pawnless tables, reduced by the eight symmetries of the board
+    fn encode_pieces(&self, squares: &mut [usize], lead: usize, material: Material) -> u64 {
+        let flip_all = |squares: &mut [usize], f: fn(usize) -> usize| squares.iter_mut().for_each(|square| *square = f(*square));
+
+        if rank_of(squares[0]) >= 4 {
+            flip_all(squares, |square| square ^ 56);
+        }
+
+        for i in 0..lead {
+            if !is_offdiagonal(squares[i]) {
+                continue;
+            }
+
+            if rank_of(squares[i]) > file_of(squares[i]) {
+                flip_all(&mut squares[i..], flip_diagonal);
+            }
+
+            break;
+        }
+
+        if material.unique_pieces() > 2 {
+            let adjust1 = (squares[1] > squares[0]) as u64;
+            let adjust2 = (squares[2] > squares[0]) as u64 + (squares[2] > squares[1]) as u64;
+
+            if is_offdiagonal(squares[0]) {
+                TRIANGLE[squares[0]] * 63 * 62 + (squares[1] as u64 - adjust1) * 62 + (squares[2] as u64 - adjust2)
+            }
+            else if is_offdiagonal(squares[1]) {
+                6 * 63 * 62 + rank_of(squares[0]) as u64 * 28 * 62 + LOWER[squares[1]] * 62 + squares[2] as u64 - adjust2
+            }
+            else if is_offdiagonal(squares[2]) {
+                6 * 63 * 62 + 4 * 28 * 62 + rank_of(squares[0]) as u64 * 7 * 28 + (rank_of(squares[1]) as u64 - adjust1) * 28 + LOWER[squares[2]]
+            }
+            else {
+                6 * 63 * 62 + 4 * 28 * 62 + 4 * 7 * 28 + rank_of(squares[0]) as u64 * 7 * 6 + (rank_of(squares[1]) as u64 - adjust1) * 6 + (rank_of(squares[2]) as u64 - adjust2)
+            }
+        }
+        else if material.unique_pieces() == 2 {
+            KK_IDX[TRIANGLE[squares[0]] as usize][squares[1]]
+        }
+        else if material.min_like_man() == 2 {
+            if TRIANGLE[squares[0]] > TRIANGLE[squares[1]] {
+                squares.swap(0, 1);
+            }
+
+            if file_of(squares[0]) >= 4 {
+                flip_all(squares, |square| square ^ 7);
+            }
+
+            if rank_of(squares[0]) >= 4 {
+                flip_all(squares, |square| square ^ 56);
+            }
+
+            if rank_of(squares[0]) > file_of(squares[0]) || (!is_offdiagonal(squares[0]) && rank_of(squares[1]) > file_of(squares[1])) {
+                flip_all(squares, flip_diagonal);
+            }
+
+            if TEST45 & (1 << squares[1]) != 0 && TRIANGLE[squares[0]] == TRIANGLE[squares[1]] {
+                squares.swap(0, 1);
+                flip_all(squares, |square| flip_diagonal(square ^ 56));
+            }
+
+            PP_IDX[TRIANGLE[squares[0]] as usize][squares[1]]
+        }
+        else {
+            for i in 1..lead {
+                if TRIANGLE[squares[0]] > TRIANGLE[squares[i]] {
+                    squares.swap(0, i);
+                }
+            }
+
+            if file_of(squares[0]) >= 4 {
+                flip_all(squares, |square| square ^ 7);
+            }
+
+            if rank_of(squares[0]) >= 4 {
+                flip_all(squares, |square| square ^ 56);
+            }
+
+            if rank_of(squares[0]) > file_of(squares[0]) {
+                flip_all(squares, flip_diagonal);
+            }
+
+            for i in 1..lead {
+                for j in i + 1..lead {
+                    if MULT_TWIST[squares[i]] > MULT_TWIST[squares[j]] {
+                        squares.swap(i, j);
+                    }
+                }
+            }
+
+            let mut index = CONSTS.mult_idx[lead - 1][TRIANGLE[squares[0]] as usize];
+
+            for (i, &square) in squares.iter().enumerate().take(lead).skip(1) {
+                index += binomial(MULT_TWIST[square], i as u64);
+            }
+
+            index
+        }
+    }
+
+    fn material_of_first(&self) -> Material {
+        Material::from_pieces(&self.files[0][0].groups.pieces)
+    }
+
+    fn probe_wdl(&self, board: &Board) -> Option<Wdl> {
+        let (pairs, index) = self.encode(board)?;
+
+        Wdl::from_raw(self.decompress(pairs, index)?)
+    }
+
+    // Plies to zeroing stored for the side to move, none when the table only stores the other side
+    fn probe_dtz(&self, board: &Board, wdl: Wdl) -> Option<Option<i32>> {
+        let Some((pairs, index)) = self.encode(board) else {
+            return Some(None);
+        };
+
+        let mut value = self.decompress(pairs, index)? as usize;
+
+        if let Some(map) = pairs.dtz_map {
+            let slot = match wdl {
+                Wdl::Win => 0,
+                Wdl::Loss => 1,
+                Wdl::CursedWin => 2,
+                Wdl::BlessedLoss => 3,
+                Wdl::Draw => return None
+            };
+
+            value = match map.wide {
+                true => self.u16_at(map.offset + 2 * (map.by_wdl[slot] + value))? as usize,
+                false => self.u8_at(map.offset + map.by_wdl[slot] + value)? as usize
+            };
+        }
+
+        let plies = match wdl {
+            Wdl::Win => pairs.flags & WIN_PLIES != 0,
+            Wdl::Loss => pairs.flags & LOSS_PLIES != 0,
+            _ => false
+        };
+
+        Some(Some(if plies { value as i32 } else { 2 * value as i32 }))
+    }
+}
+
+// Paths of every known table, the files are only read the first time a table is probed
+#[derive(Debug, Default)]
+pub struct Tablebase {
+    wdl: HashMap<Material, (PathBuf, OnceLock<Option<Table>>)>,
+    dtz: HashMap<Material, (PathBuf, OnceLock<Option<Table>>)>,
+    max_pieces: usize
+}
+
+impl Tablebase {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn max_pieces(&self) -> usize {
+        self.max_pieces
+    }
+
+    // Adds the tables of every directory in a list separated the same way as PATH
+    pub fn add_path(&mut self, path: &str) -> usize {
+        std::env::split_paths(path).map(|directory| self.add_directory(&directory)).sum()
+    }
+
+    pub fn add_directory(&mut self, directory: &Path) -> usize {
+        let Ok(entries) = std::fs::read_dir(directory) else {
+            return 0;
+        };
+
+        entries.flatten().filter(|entry| self.add_file(&entry.path())).count()
+    }
+
+    fn add_file(&mut self, path: &Path) -> bool {
+        let Some(material) = path.file_stem().and_then(|stem| stem.to_str()).and_then(Material::from_name) else {
+            return false;
+        };
+
+        let tables = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("rtbw") => &mut self.wdl,
+            Some("rtbz") => &mut self.dtz,
+            _ => return false
+        };
+
+        if !path.metadata().is_ok_and(|metadata| metadata.is_file() && metadata.len() % 64 == 16) {
+            return false;
+        }
+
+        tables.insert(material, (path.to_path_buf(), OnceLock::new()));
+        self.max_pieces = self.max_pieces.max(material.count());
+
+        true
+    }
+
+    fn table(tables: &HashMap<Material, (PathBuf, OnceLock<Option<Table>>)>, material: Material, dtz: bool) -> Option<&Table> {
+        let (path, table) = tables.get(&material).or_else(|| tables.get(&material.flipped()))?;
+
+        table
+            .get_or_init(|| std::fs::read(path).ok().and_then(|data| Table::new(data, material, dtz)))
+            .as_ref()
+    }
+
+    // Tables don't know about castling and only cover positions up to the largest table found
+    pub fn is_usable(&self, board: &Board) -> bool {
+        CastleKind::all().all(|kind| !board.state.castles().is_allowed(kind)) && board.state.occupied().count() <= self.max_pieces
+    }
+
+    // Outcome of the position assuming the halfmove clock was just reset
+    pub fn probe_wdl(&self, board: &mut Board) -> Option<Wdl> {
+        if !self.is_usable(board) {
+            return None;
+        }
+
+        self.probe(board).map(|(wdl, _)| wdl)
+    }
+
+    // Plies to the next zeroing move in a best play, positive for the winning side
+    pub fn probe_dtz(&self, board: &mut Board) -> Option<i32> {
+        if !self.is_usable(board) {
+            return None;
+        }
+
+        self.dtz(board)
+    }
+
+    // The legal moves that keep the best outcome reachable under the 50 move rule, winning moves are further limited to those with the shortest dtz
+    pub fn root_moves(&self, board: &mut Board) -> Option<Vec<Move>> {
+        if !self.is_usable(board) {
+            return None;
+        }
+
+        let halfmove = board.state.halfmove() as i32;
+        let mut ranked = Vec::new();
+
+        for mv in legal_moves(board) {
+            let zeroing = board.is_noisy(mv) || board.state.at(mv.from()).is_some_and(|piece| piece.kind() == PieceKind::Pawn);
+
+            board.make(mv);
+
+            let dtz = match zeroing {
+                true => self.probe(board).map(|(wdl, _)| (-wdl).before_zeroing()),
+                false => self.dtz(board).map(|dtz| if dtz == 1 && is_checkmate(board) { 1 } else { add_plies(-dtz, 1) })
+            };
+
+            board.unmake();
+
+            let dtz = dtz?;
+            let clock = if zeroing { 0 } else { halfmove };
+
+            let rank = match dtz {
+                _ if dtz > 0 && dtz + clock <= 100 => Wdl::Win,
+                _ if dtz > 0 => Wdl::CursedWin,
+                _ if dtz < 0 && clock - dtz <= 100 => Wdl::Loss,
+                _ if dtz < 0 => Wdl::BlessedLoss,
+                _ => Wdl::Draw
+            };
+
+            ranked.push((mv, rank, dtz));
+        }
+
+        let best = ranked.iter().map(|&(_, rank, _)| rank).max()?;
+        ranked.retain(|&(_, rank, _)| rank == best);
+
+        // Losing moves are ranked by the most negative dtz, so the same minimum gives the longest resistance
+        if best != Wdl::Draw {
+            let shortest = ranked.iter().map(|&(_, _, dtz)| dtz).min()?;
+            ranked.retain(|&(_, _, dtz)| dtz == shortest);
+        }
+
+        Some(ranked.into_iter().map(|(mv, _, _)| mv).collect())
+    }
+
+    // Resolves captures first since tables may store any lower value for positions with a capture reaching a better outcome, the flag tells if the best move zeroes the halfmove clock
+    fn probe(&self, board: &mut Board) -> Option<(Wdl, bool)> {
+        let moves = legal_moves(board);
+
+        let mut best_capture = Wdl::Loss;
+        let mut best_enpassant = Wdl::Loss;
+
+        for mv in captures(board, &moves) {
+            board.make(mv);
+            let wdl = self.probe_ab_no_ep(board, Wdl::Loss, -best_capture).map(|wdl| -wdl);
+            board.unmake();
+
+            let wdl = wdl?;
+
+            if wdl == Wdl::Win {
+                return Some((wdl, true));
+            }
+
+            match mv.is_enpassant() {
+                true => best_enpassant = best_enpassant.max(wdl),
+                false => best_capture = best_capture.max(wdl)
+            }
+        }
+
+        let wdl = self.probe_wdl_table(board)?;
+
+        // An en passant capture that is strictly better than the position without en passant rights
+        if best_enpassant > wdl.max(best_capture) {
+            return Some((best_enpassant, true));
+        }
+
+        best_capture = best_capture.max(best_enpassant);
+
+        if best_capture >= wdl {
+            return Some((best_capture, best_capture > Wdl::Draw));
+        }
+
+        // The table reports a stalemate that en passant captures avoid
+        if wdl == Wdl::Draw && !moves.is_empty() && moves.iter().all(|mv| mv.is_enpassant()) {
+            return Some((best_enpassant, true));
+        }
+
+        Some((wdl, false))
+    }
+
+    fn probe_ab_no_ep(&self, board: &mut Board, mut alpha: Wdl, beta: Wdl) -> Option<Wdl> {
+        for mv in captures(board, &legal_moves(board)) {
+            board.make(mv);
+            let wdl = self.probe_ab_no_ep(board, -beta, -alpha).map(|wdl| -wdl);
+            board.unmake();
+
+            let wdl = wdl?;
+
+            if wdl >= beta {
+                return Some(wdl);
+            }
+
+            alpha = alpha.max(wdl);
+        }
+
+        Some(alpha.max(self.probe_wdl_table(board)?))
+    }
+
+    fn probe_wdl_table(&self, board: &Board) -> Option<Wdl> {
+        if board.state.pieces(PieceKind::King) == board.state.occupied() {
+            return Some(Wdl::Draw);
+        }
+
+        Self::table(&self.wdl, Material::from_board(board), false)?.probe_wdl(board)
+    }
+
+    fn dtz(&self, board: &mut Board) -> Option<i32> {
+        let (wdl, zeroing) = self.probe(board)?;
+
+        if wdl == Wdl::Draw {
+            return Some(0);
+        }
+
+        if zeroing {
+            return Some(wdl.before_zeroing());
+        }
+
+        // Captures are already resolved, a winning side may still zero with a pawn push
+        if wdl >= Wdl::CursedWin {
+            let pushes = legal_moves(board).into_iter().filter(|&mv| !is_capture(board, mv) && board.state.at(mv.from()).is_some_and(|piece| piece.kind() == PieceKind::Pawn));
+
+            for mv in pushes.collect::<Vec<_>>() {
+                board.make(mv);
+                let after = self.probe(board).map(|(wdl, _)| -wdl);
+                board.unmake();
+
+                if after? == wdl {
+                    return Some(wdl.before_zeroing());
+                }
+            }
+        }
+
+        if let Some(plies) = Self::table(&self.dtz, Material::from_board(board), true)?.probe_dtz(board, wdl)? {
+            return Some(add_plies(wdl.before_zeroing(), plies));
+        }
+
+        // The table only stores the other side to move, search one ply through the moves that don't zero
+        let mut best = match wdl >= Wdl::CursedWin {
+            true => None,
+            false => Some(wdl.before_zeroing())
+        };
+
+        let moves = legal_moves(board).into_iter().filter(|&mv| !board.is_noisy(mv) && board.state.at(mv.from()).is_some_and(|piece| piece.kind() != PieceKind::Pawn));
+
+        for mv in moves.collect::<Vec<_>>() {
+            board.make(mv);
+            let dtz = self.dtz(board).map(|dtz| (-dtz, dtz == -1 && is_checkmate(board)));
+            board.unmake();
+
+            let (dtz, mate) = dtz?;
+
+            if mate {
+                best = Some(1);
+            }
+            else if dtz.signum() == wdl.signum() {
+                let dtz = add_plies(dtz, 1);
+
+                best = Some(best.map_or(dtz, |best| best.min(dtz)));
+            }
+        }
+
+        best
+    }
+}
+
+fn legal_moves(board: &Board) -> Vec<Move> {
+    board.generate_moves().iter_moves().copied().filter(|&mv| board.is_legal(mv)).collect()
+}
+
+fn captures(board: &Board, moves: &[Move]) -> Vec<Move> {
+    moves.iter().copied().filter(|&mv| is_capture(board, mv)).collect()
+}
+
+fn is_capture(board: &Board, mv: Move) -> bool {
+    board.state.at(mv.to()).is_some() || mv.is_enpassant()
+}
+
+fn is_checkmate(board: &Board) -> bool {
+    board.state.checkers().is_some() && legal_moves(board).is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chess::random::Random;
+
+    // Expected results from the Syzygy test suite, as wdl after a zeroing move and dtz
+    const POSITIONS: [(&str, i32, i32); 14] = [
+        ("8/3k4/8/8/8/8/4P3/3K4 w - - 0 1", 0, 0),
+        ("8/5p2/6k1/K7/8/8/8/8 w - - 0 1", -2, -2),
+        ("8/8/8/2R5/1K6/8/5k2/8 w - - 0 1", 2, 21),
+        ("6k1/8/8/8/8/4n3/8/K7 b - - 0 1", 0, 0),
+        ("q7/k2K4/5B2/8/8/8/8/8 b - - 0 1", 2, 19),
+        ("3B4/K7/8/k3N3/8/8/8/8 b - - 0 1", -2, -53),
+        ("8/6k1/4K3/6B1/8/8/5N2/8 w - - 0 1", 2, 20),
+        ("4n3/8/7b/8/8/7K/8/7k w - - 0 1", -2, -51),
+        ("8/8/2r5/6bk/8/8/6K1/8 b - - 0 1", 2, 9),
+        ("8/4K3/8/2k5/8/8/8/5Q1N b - - 0 1", -2, -12),
+        ("8/8/3P1K1p/1k6/8/8/8/8 b - - 0 1", -2, -3),
+        ("8/7k/8/4KP2/8/8/1p6/8 w - - 0 1", -2, -1),
+        ("8/2k5/p7/3K2P1/8/8/8/8 b - - 0 1", 0, 0),
+        ("8/8/1k6/3K4/1b5r/8/8/8 w - - 0 1", -2, -16)
+    ];
+
+    fn wdl_value(wdl: Wdl) -> i32 {
+        wdl as i32 - 2
+    }
+
+    #[test]
+    fn material() {
+        let material = Material::from_name("KRPvKR").unwrap();
+
+        assert_eq!(material.count(), 5);
+        assert!(material.has_pawns());
+        assert!(!material.is_symmetric());
+        assert_eq!(material.flipped(), Material::from_name("KRvKRP").unwrap());
+        assert_eq!(Material::from_board(&Board::from_fen("8/8/8/3k4/8/2r5/1P6/R3K3 w - - 0 1").unwrap()), material);
+
+        assert!(Material::from_name("KvK").unwrap().is_symmetric());
+        assert_eq!(Material::from_name("KBNvK").unwrap().unique_pieces(), 4);
+        assert_eq!(Material::from_name("KNNvKP").unwrap().min_like_man(), 2);
+
+        assert_eq!(Material::from_name("KQv"), None);
+        assert_eq!(Material::from_name("KXvK"), None);
+        assert_eq!(Material::from_name("KQRBNPvKQ"), None);
+    }
+
+    // Tables are read from TEA_SYZYGY_PATH or res/syzygy with cargo test -- --ignored, positions without their tables are skipped
+    #[test]
+    #[ignore = "needs the syzygy tables"]
+    fn probe() {
+        let mut tablebase = Tablebase::new();
+        let mut probed = 0;
+
+        tablebase.add_path(&std::env::var("TEA_SYZYGY_PATH").unwrap_or("res/syzygy".to_string()));
+
+        for (fen, wdl, dtz) in POSITIONS {
+            let mut board = Board::from_fen(fen).unwrap();
+
+            let Some(result) = tablebase.probe_wdl(&mut board) else {
+                continue;
+            };
+
+            probed += 1;
+
+            assert_eq!(wdl_value(result), wdl, "{}", fen);
+            assert_eq!(tablebase.probe_dtz(&mut board), Some(dtz), "{}", fen);
+
+            // Every move kept at the root reaches the same outcome
+            for mv in tablebase.root_moves(&mut board).unwrap() {
+                board.make(mv);
+
+                let after = tablebase.probe_dtz(&mut board).unwrap();
+
+                board.unmake();
+
+                assert_eq!(after.signum(), -dtz.signum(), "{} {}", fen, mv);
+            }
+        }
+
+        assert!(probed > 0, "no syzygy tables found");
+    }
+
+    // Garbage after a valid header must never panic, only fail the probe
+    #[test]
+    fn corrupt() {
+        let directory = std::env::temp_dir().join(format!("tea-syzygy-{}", std::process::id()));
+        let mut random = Random::new(35);
+
+        std::fs::create_dir_all(&directory).unwrap();
+
+        for (name, magic) in [("KQvK.rtbw", WDL_MAGIC), ("KQvK.rtbz", DTZ_MAGIC), ("KPvK.rtbw", WDL_MAGIC), ("KRvKN.rtbw", WDL_MAGIC)] {
+            let mut data: Vec<u8> = (0..64 * 64 + 16).map(|_| random.next_u64() as u8).collect();
+
+            data[0..4].copy_from_slice(&magic);
+
+            std::fs::write(directory.join(name), data).unwrap();
+        }
+
+        let mut tablebase = Tablebase::new();
+
+        assert_eq!(tablebase.add_directory(&directory), 4);
+        assert_eq!(tablebase.max_pieces(), 4);
+
+        for fen in ["8/8/8/3k4/8/8/8/Q3K3 w - - 0 1", "8/8/8/3k4/8/8/4P3/4K3 b - - 0 1", "8/8/2n5/3k4/8/8/8/R3K3 w - - 0 1"] {
+            let mut board = Board::from_fen(fen).unwrap();
+
+            tablebase.probe_wdl(&mut board);
+            tablebase.probe_dtz(&mut board);
+            tablebase.root_moves(&mut board);
+        }
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
\ No newline at end of file
@@ -0,0 +1,110 @@
+use std::sync::atomic::Ordering;
+
+use crate::{chess::moves::Move, engine::{data::{Data, RootMove, Shared}, search::{Limits, Search}, syzygy::Tablebase, tt::TT}};
+
+// Lazy SMP, every thread searches the same position and they only talk through the transposition table, returns the picked thread's best root move
+pub fn search(threads: &mut [Data], tt: &TT, tablebase: &Tablebase, shared: &Shared, limits: Limits) -> Option<RootMove> {
+    let (main, helpers) = threads.split_first_mut().expect("no search thread");
+
+    let (result, results) = std::thread::scope(|scope| {
+        let handles: Vec<_> = helpers.iter_mut().map(|data| {
+            scope.spawn(move || {
+                let result = Search::new(data, tt, tablebase, shared, limits).run();
+
+                (result, data.depth)
+            })
+        }).collect();
+
+        let result = Search::new(main, tt, tablebase, shared, limits).run();
+
+        // Helpers keep going until the main thread is done
+        shared.stop.store(true, Ordering::Relaxed);
+
+        let results: Vec<_> = handles.into_iter().map(|handle| handle.join().expect("search thread panicked")).collect();
+
+        (result, results)
+    });
+
+    let results: Vec<_> = std::iter::once((result, main.depth)).chain(results).collect();
+
+    // Helpers don't search the other lines, so the main thread's best line stays the first one
+    let index = if limits.multipv > 1 { 0 } else { vote(&results) };
+
+    threads[index].root_moves.first().copied()
+}
+
+// Each thread votes for its move weighted by score and depth, ties go to the lowest thread so the pick doesn't depend on timing
+fn vote(results: &[((Move, i32), i32)]) -> usize {
+    let min = results.iter().map(|&((_, score), _)| score).min().unwrap_or(0);
+    let weight = |&((mv, score), depth): &((Move, i32), i32)| (mv, (score - min + 14) as i64 * depth as i64);
+
+    let votes = |mv: Move| results.iter().map(weight).filter(|&(other, _)| other == mv).map(|(_, weight)| weight).sum::<i64>();
+
+    (0..results.len()).fold(0, |best, i| {
+        match results[i].1 > 0 && results[i].0.0.is_some() && votes(results[i].0.0) > votes(results[best].0.0) {
+            true => i,
+            false => best
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chess::{board::Board, moves::MoveKind, square::Square};
+
+    #[test]
+    fn vote() {
+        let a = Move::new(Square::E2, Square::E4, MoveKind::Normal);
+        let b = Move::new(Square::D2, Square::D4, MoveKind::Normal);
+
+        assert_eq!(super::vote(&[((a, 20), 10)]), 0);
+        assert_eq!(super::vote(&[((a, 20), 10), ((b, 20), 10)]), 0);
+        assert_eq!(super::vote(&[((a, 20), 10), ((b, 20), 10), ((b, 10), 9)]), 1);
+        assert_eq!(super::vote(&[((a, 20), 10), ((b, 60), 12)]), 1);
+
+        // Threads stopped before finishing an iteration don't vote
+        assert_eq!(super::vote(&[((a, 20), 10), ((b, 500), 0), ((b, 500), 0)]), 0);
+    }
+
+    #[test]
+    fn threads() {
+        let tt = TT::new(4);
+        let tablebase = Tablebase::new();
+        let shared = Shared::new(3);
+        let board = Board::from_fen("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1").unwrap();
+        let mut threads: Vec<Data> = (0..3).map(|id| Data::new(id, board.clone())).collect();
+
+        let best = search(&mut threads, &tt, &tablebase, &shared, Limits { depth: 5, ..Limits::new() }).unwrap();
+
+        assert_eq!(best.mv.to_string(), "a1a8");
+        assert!(threads.iter().all(|data| data.nodes > 0));
+        assert_eq!(shared.nodes(), threads.iter().map(|data| data.nodes).sum::<u64>());
+    }
+
+    #[test]
+    fn ponder() {
+        let tt = TT::new(4);
+        let tablebase = Tablebase::new();
+        let shared = Shared::new(1);
+        let mut threads = vec![Data::new(0, Board::startpos().unwrap())];
+
+        shared.ponder.store(true, Ordering::Relaxed);
+
+        // The move time only starts to matter once pondering ends
+        std::thread::scope(|scope| {
+            let search = scope.spawn(|| search(&mut threads, &tt, &tablebase, &shared, Limits { depth: 3, movetime: Some(1), ..Limits::new() }));
+
+            std::thread::sleep(std::time::Duration::from_millis(100));
+            assert!(!search.is_finished());
+
+            shared.ponder.store(false, Ordering::Relaxed);
+
+            let best = search.join().unwrap().unwrap();
+
+            assert!(best.mv.is_some() && best.pv.len() >= 2);
+        });
+
+        assert_eq!(threads[0].depth, 3);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,85 @@
+use std::time::{Duration, Instant};
+
+use crate::{chess::color::Color, engine::search::Limits};
+
+// Moves the remaining time is split across when the gui doesn't say
+const MOVES_TO_GO: u64 = 25;
+
+#[derive(Debug, Clone, Copy)]
+pub struct TimeManager {
+    start: Instant,
+    soft: Option<Duration>,
+    hard: Option<Duration>
+}
+
+impl TimeManager {
+    // The soft limit ends the search between iterations, the hard limit in the middle of one, a fixed move time only has the latter
+    pub fn new(limits: &Limits, color: Color) -> Self {
+        let (soft, hard) = match (limits.movetime, limits.time[color]) {
+            (Some(movetime), _) => (None, Some(movetime.saturating_sub(limits.overhead).max(1))),
+            (None, Some(time)) => {
+                let time = time.saturating_sub(limits.overhead).max(1);
+                let moves = limits.movestogo.unwrap_or(MOVES_TO_GO).clamp(1, MOVES_TO_GO);
+                let base = time / moves + limits.inc[color] * 3 / 4;
+                let hard = (base * 3).min(time * 3 / 4).max(1);
+
+                (Some((base * 3 / 5).min(hard)), Some(hard))
+            },
+            _ => (None, None)
+        };
+
+        Self {
+            start: Instant::now(),
+            soft: soft.map(Duration::from_millis),
+            hard: hard.map(Duration::from_millis)
+        }
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    pub fn is_hard_stop(&self) -> bool {
+        self.hard.is_some_and(|hard| self.elapsed() >= hard)
+    }
+
+    // Stops sooner when the best move kept stable for a few iterations and took most of the nodes
+    pub fn is_soft_stop(&self, stability: usize, fraction: f64) -> bool {
+        let Some(soft) = self.soft else {
+            return false;
+        };
+
+        let stability = [2.5, 1.2, 0.9, 0.8, 0.75][stability.min(4)];
+        let fraction = (1.5 - fraction.clamp(0.0, 1.0)) * 1.35;
+
+        self.elapsed() >= soft.mul_f64(stability * fraction).min(self.hard.unwrap_or(soft))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limits() {
+        let time = TimeManager::new(&Limits { movetime: Some(1000), overhead: 50, ..Limits::new() }, Color::White);
+
+        assert_eq!((time.soft, time.hard), (None, Some(Duration::from_millis(950))));
+
+        let limits = Limits { time: [Some(60000), Some(1000)], inc: [1000, 0], overhead: 10, ..Limits::new() };
+        let white = TimeManager::new(&limits, Color::White);
+        let black = TimeManager::new(&limits, Color::Black);
+
+        assert!(white.soft < white.hard && white.hard < Some(Duration::from_millis(60000 * 3 / 4)));
+        assert!(black.hard < white.soft);
+
+        // A single move left may use most of the clock
+        let last = TimeManager::new(&Limits { time: [Some(10000), None], movestogo: Some(1), ..Limits::new() }, Color::White);
+
+        assert!(last.hard > Some(Duration::from_millis(5000)));
+
+        let infinite = TimeManager::new(&Limits { infinite: true, ..Limits::new() }, Color::White);
+
+        assert!(!infinite.is_hard_stop() && !infinite.is_soft_stop(10, 1.0));
+    }
+}
\ No newline at end of file
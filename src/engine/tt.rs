@@ -0,0 +1,167 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::chess::{board::Key, moves::Move};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[repr(u8)]
+pub enum Bound {
+    None,
+    Upper,
+    Lower,
+    Exact
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct TTEntry {
+    pub mv: Move,
+    pub score: i32,
+    pub eval: i32,
+    pub depth: i32,
+    pub bound: Bound
+}
+
+// The key is stored xored with the data, so an entry torn by a concurrent write never matches
+#[derive(Debug, Default)]
+struct Slot {
+    key: AtomicU64,
+    data: AtomicU64
+}
+
+#[derive(Debug)]
+pub struct TT {
+    slots: Vec<Slot>
+}
+
+impl Bound {
+    #[inline(always)]
+    pub const fn from_raw(value: u8) -> Self {
+        match value {
+            0 => Bound::None,
+            1 => Bound::Upper,
+            2 => Bound::Lower,
+            3 => Bound::Exact,
+            _ => panic!("invalid index!")
+        }
+    }
+}
+
+impl TTEntry {
+    // Move, score, eval, depth and bound packed into 16, 16, 16, 8 and 8 bits
+    #[inline(always)]
+    const fn pack(self) -> u64 {
+        self.mv.value() as u64 |
+        (self.score as i16 as u16 as u64) << 16 |
+        (self.eval as i16 as u16 as u64) << 32 |
+        (self.depth as u8 as u64) << 48 |
+        (self.bound as u64) << 56
+    }
+
+    #[inline(always)]
+    const fn unpack(data: u64) -> Self {
+        Self {
+            mv: Move::from_raw(data as u16),
+            score: (data >> 16) as u16 as i16 as i32,
+            eval: (data >> 32) as u16 as i16 as i32,
+            depth: (data >> 48) as u8 as i32,
+            bound: Bound::from_raw((data >> 56) as u8 & 3)
+        }
+    }
+}
+
+impl TT {
+    pub const DEFAULT_SIZE: usize = 16;
+
+    // Size in megabytes
+    pub fn new(size: usize) -> Self {
+        let count = size.max(1) * 1024 * 1024 / std::mem::size_of::<Slot>();
+
+        Self {
+            slots: (0..count).map(|_| Slot::default()).collect()
+        }
+    }
+
+    pub fn clear(&self) {
+        for slot in self.slots.iter() {
+            slot.key.store(0, Ordering::Relaxed);
+            slot.data.store(0, Ordering::Relaxed);
+        }
+    }
+
+    #[inline(always)]
+    fn slot(&self, key: Key) -> &Slot {
+        &self.slots[((key.main as u128 * self.slots.len() as u128) >> 64) as usize]
+    }
+
+    pub fn probe(&self, key: Key) -> Option<TTEntry> {
+        let slot = self.slot(key);
+        let data = slot.data.load(Ordering::Relaxed);
+
+        match slot.key.load(Ordering::Relaxed) ^ data == key.main && data != 0 {
+            true => Some(TTEntry::unpack(data)),
+            false => None
+        }
+    }
+
+    // Deeper and exact entries of the same position are kept unless the new search is nearly as deep
+    pub fn store(&self, key: Key, mut entry: TTEntry) {
+        let slot = self.slot(key);
+        let data = slot.data.load(Ordering::Relaxed);
+        let same = slot.key.load(Ordering::Relaxed) ^ data == key.main;
+
+        if same {
+            let old = TTEntry::unpack(data);
+
+            if entry.mv.is_null() {
+                entry.mv = old.mv;
+            }
+
+            if entry.bound != Bound::Exact && old.depth > entry.depth + 2 {
+                return;
+            }
+        }
+
+        let data = entry.pack();
+
+        slot.key.store(key.main ^ data, Ordering::Relaxed);
+        slot.data.store(data, Ordering::Relaxed);
+    }
+
+    // Permille of the table in use, sampled from the first thousand slots
+    pub fn hashfull(&self) -> usize {
+        self.slots.iter().take(1000).filter(|slot| slot.data.load(Ordering::Relaxed) != 0).count() * 1000 / self.slots.len().min(1000)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chess::{moves::MoveKind, square::Square};
+
+    #[test]
+    fn store_and_probe() {
+        let tt = TT::new(1);
+        let key = Key { main: 0x1234_5678_9abc_def0 };
+        let mv = Move::new(Square::E2, Square::E4, MoveKind::Normal);
+        let entry = TTEntry { mv, score: -300, eval: 25, depth: 7, bound: Bound::Lower };
+
+        assert_eq!(tt.probe(key), None);
+
+        tt.store(key, entry);
+
+        assert_eq!(tt.probe(key), Some(entry));
+        assert_eq!(tt.probe(Key { main: key.main ^ 1 }), None);
+
+        // A shallow search keeps the stored move and doesn't replace a deep entry
+        tt.store(key, TTEntry { mv: Move::NULL, score: 0, eval: 0, depth: 1, bound: Bound::Upper });
+
+        assert_eq!(tt.probe(key), Some(entry));
+
+        tt.store(key, TTEntry { mv: Move::NULL, score: 10, eval: 0, depth: 6, bound: Bound::Exact });
+
+        assert_eq!(tt.probe(key).map(|entry| (entry.mv, entry.score)), Some((mv, 10)));
+
+        tt.clear();
+
+        assert_eq!(tt.probe(key), None);
+    }
+}
\ No newline at end of file
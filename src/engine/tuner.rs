@@ -0,0 +1,375 @@
+use std::io::BufRead;
+
+use crate::{chess::{board::Board, piece::PieceKind, square::Square}, engine::eval::{self, FEATURES, PHASE_MAX, Trace, material_index, pst_index}};
+
+const NAMES: [&str; PieceKind::COUNT] = ["Pawn", "Knight", "Bishop", "Rook", "Queen", "King"];
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Optimizer {
+    Adam,
+    Gradient
+}
+
+// A traced position with its game result and search score, both from white's point of view
+#[derive(Debug, Clone)]
+struct Entry {
+    trace: Trace,
+    result: Option<f64>,
+    score: Option<i32>
+}
+
+// Fits the evaluation terms to labelled positions by minimizing the squared error of the predicted result
+#[derive(Debug, Clone)]
+pub struct Tuner {
+    entries: Vec<Entry>,
+    weights: Vec<[f64; 2]>,
+    k: f64,
+    threads: usize
+}
+
+impl Optimizer {
+    // Plain gradient steps are much smaller, so they need a bigger rate
+    pub const fn default_rate(self) -> f64 {
+        match self {
+            Optimizer::Adam => 1.0,
+            Optimizer::Gradient => 100000.0
+        }
+    }
+}
+
+#[inline(always)]
+fn sigmoid(k: f64, score: f64) -> f64 {
+    1.0 / (1.0 + (-k * score / 400.0).exp())
+}
+
+// Results are 1-0, 0-1, 1/2-1/2 or a decimal between 0 and 1
+fn parse_result(text: &str) -> Option<f64> {
+    match text.trim().trim_matches(|c| matches!(c, '"' | ';' | '[' | ']')) {
+        "1-0" => Some(1.0),
+        "0-1" => Some(0.0),
+        "1/2-1/2" => Some(0.5),
+        text if text.contains('.') => text.parse().ok().filter(|result| (0.0..=1.0).contains(result)),
+        _ => None
+    }
+}
+
+// Reads "fen [result]", "fen c9 \"result\";", "fen | result", "fen | score" and "fen | score | result"
+fn parse(line: &str) -> Option<(Board, Option<f64>, Option<i32>)> {
+    let (fen, label) = line.split_at(line.find(['[', '|', '"'])?);
+    let fen = fen.trim().trim_end_matches("c9").trim();
+    let board = Board::from_fen(fen).ok()?;
+
+    // Brackets and quotes only hold results, where a bare 1 or 0 is a win or a loss rather than a score
+    if !label.starts_with('|') {
+        let result = match label.trim_matches(|c| matches!(c, '"' | ';' | '[' | ']')).trim() {
+            "1" => 1.0,
+            "0" => 0.0,
+            text => parse_result(text)?
+        };
+
+        return Some((board, Some(result), None));
+    }
+
+    let fields: Vec<&str> = label.split('|').map(str::trim).filter(|field| !field.is_empty()).collect();
+
+    let (result, score) = match fields.as_slice() {
+        [field] => match parse_result(field) {
+            Some(result) => (Some(result), None),
+            None => (None, Some(field.parse().ok()?))
+        },
+        [score, result] => (Some(parse_result(result)?), Some(score.parse().ok()?)),
+        _ => return None
+    };
+
+    Some((board, result, score))
+}
+
+impl Entry {
+    // Scores go through the same sigmoid as the evaluation, so a score equal to the evaluation is no error, a position with both labels aims between them
+    fn target(&self, k: f64) -> f64 {
+        match (self.result, self.score) {
+            (Some(result), Some(score)) => (result + sigmoid(k, score as f64)) / 2.0,
+            (Some(result), None) => result,
+            (None, Some(score)) => sigmoid(k, score as f64),
+            (None, None) => 0.5
+        }
+    }
+}
+
+impl Tuner {
+    // Starts from the current evaluation
+    pub fn new(threads: usize) -> Self {
+        Self {
+            entries: Vec::new(),
+            weights: eval::weights().iter().map(|&[mg, eg]| [mg as f64, eg as f64]).collect(),
+            k: 1.0,
+            threads: threads.max(1)
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn k(&self) -> f64 {
+        self.k
+    }
+
+    pub fn add(&mut self, board: &Board, result: Option<f64>, score: Option<i32>) {
+        self.entries.push(Entry { trace: eval::trace(board), result, score });
+    }
+
+    // Returns the number of positions added and lines skipped
+    pub fn load(&mut self, reader: impl BufRead) -> (usize, usize) {
+        let mut added = 0;
+        let mut skipped = 0;
+
+        for line in reader.lines().map_while(Result::ok).filter(|line| !line.trim().is_empty()) {
+            match parse(&line) {
+                Some((board, result, score)) => {
+                    self.add(&board, result, score);
+                    added += 1;
+                },
+                None => skipped += 1
+            }
+        }
+
+        (added, skipped)
+    }
+
+    #[inline(always)]
+    fn evaluate(&self, entry: &Entry) -> f64 {
+        let (mg, eg) = entry.trace.terms.iter().fold((0.0, 0.0), |(mg, eg), &(i, coefficient)| {
+            let [wmg, weg] = self.weights[i as usize];
+
+            (mg + wmg * coefficient as f64, eg + weg * coefficient as f64)
+        });
+
+        let phase = entry.trace.phase as f64 / PHASE_MAX as f64;
+
+        mg * phase + eg * (1.0 - phase)
+    }
+
+    // Splits the entries across threads and adds up what each thread returns
+    fn reduce<T: Send>(&self, map: impl Fn(&[Entry]) -> T + Sync, mut fold: impl FnMut(T)) {
+        let size = self.entries.len().div_ceil(self.threads).max(1);
+
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = self.entries.chunks(size).map(|chunk| scope.spawn(|| map(chunk))).collect();
+
+            for handle in handles {
+                fold(handle.join().expect("tuner thread panicked"));
+            }
+        });
+    }
+
+    pub fn error(&self) -> f64 {
+        let mut error = 0.0;
+
+        self.reduce(|chunk| chunk.iter().map(|entry| (entry.target(self.k) - sigmoid(self.k, self.evaluate(entry))).powi(2)).sum::<f64>(), |sum| error += sum);
+
+        error / self.entries.len().max(1) as f64
+    }
+
+    // Only game results, scores go through k on both sides and would pull it to 0 where everything is a draw
+    fn result_error(&self, k: f64) -> f64 {
+        let mut error = 0.0;
+
+        self.reduce(|chunk| chunk.iter().filter_map(|entry| Some((entry.result? - sigmoid(k, self.evaluate(entry))).powi(2))).sum::<f64>(), |sum| error += sum);
+
+        error
+    }
+
+    // Scaling constant of the sigmoid that best fits the current evaluation to the results, narrowing the search range a digit at a time
+    pub fn fit_k(&mut self) -> f64 {
+        let mut best = (self.result_error(self.k), self.k);
+        let mut step = 1.0;
+
+        for _ in 0..6 {
+            let start = (best.1 - step * 10.0).max(0.0);
+
+            for k in (0..=20).map(|i| start + step * i as f64) {
+                let error = self.result_error(k);
+
+                if error < best.0 {
+                    best = (error, k);
+                }
+            }
+
+            step /= 10.0;
+        }
+
+        self.k = best.1;
+        self.k
+    }
+
+    // Derivative of the mean squared error by every middlegame and endgame weight
+    fn gradient(&self) -> Vec<[f64; 2]> {
+        let mut gradient = vec![[0.0; 2]; FEATURES];
+
+        self.reduce(|chunk| {
+            let mut gradient = vec![[0.0; 2]; FEATURES];
+
+            for entry in chunk {
+                let sigmoid = sigmoid(self.k, self.evaluate(entry));
+                let loss = (sigmoid - entry.target(self.k)) * sigmoid * (1.0 - sigmoid);
+                let phase = entry.trace.phase as f64 / PHASE_MAX as f64;
+
+                for &(i, coefficient) in &entry.trace.terms {
+                    gradient[i as usize][0] += loss * coefficient as f64 * phase;
+                    gradient[i as usize][1] += loss * coefficient as f64 * (1.0 - phase);
+                }
+            }
+
+            gradient
+        }, |partial| {
+            for (sum, part) in gradient.iter_mut().zip(partial) {
+                sum[0] += part[0];
+                sum[1] += part[1];
+            }
+        });
+
+        let scale = 2.0 * self.k / 400.0 / self.entries.len().max(1) as f64;
+
+        gradient.iter().map(|&[mg, eg]| [mg * scale, eg * scale]).collect()
+    }
+
+    // Full batch steps, the callback gets each epoch and its error
+    pub fn run(&mut self, optimizer: Optimizer, epochs: usize, rate: f64, mut report: impl FnMut(usize, f64)) {
+        const BETA1: f64 = 0.9;
+        const BETA2: f64 = 0.999;
+        const EPSILON: f64 = 1e-8;
+
+        let mut momentum = vec![[0.0; 2]; FEATURES];
+        let mut velocity = vec![[0.0; 2]; FEATURES];
+
+        for epoch in 1..=epochs {
+            let gradient = self.gradient();
+
+            for (i, weight) in self.weights.iter_mut().enumerate() {
+                for phase in 0..2 {
+                    let gradient = gradient[i][phase];
+
+                    weight[phase] -= match optimizer {
+                        Optimizer::Adam => {
+                            momentum[i][phase] = BETA1 * momentum[i][phase] + (1.0 - BETA1) * gradient;
+                            velocity[i][phase] = BETA2 * velocity[i][phase] + (1.0 - BETA2) * gradient * gradient;
+
+                            let momentum = momentum[i][phase] / (1.0 - BETA1.powi(epoch as i32));
+                            let velocity = velocity[i][phase] / (1.0 - BETA2.powi(epoch as i32));
+
+                            rate * momentum / (velocity.sqrt() + EPSILON)
+                        },
+                        Optimizer::Gradient => rate * gradient
+                    };
+                }
+            }
+
+            report(epoch, self.error());
+        }
+    }
+
+    // The tuned tables laid out like the ones in the evaluation, ready to paste over them
+    pub fn source(&self) -> String {
+        let round = |i: usize, phase: usize| self.weights[i][phase].round() as i32;
+        let mut source = String::new();
+
+        for (name, phase) in [("MATERIAL_MG", 0), ("MATERIAL_EG", 1)] {
+            let values: Vec<String> = PieceKind::all().map(|kind| round(material_index(kind), phase).to_string()).collect();
+
+            source += &format!("const {}: [i32; PieceKind::COUNT] = [{}];\n", name, values.join(", "));
+        }
+
+        source += "\n// Piece square tables from white's point of view, starting at a1\n";
+
+        for (name, phase) in [("PST_MG", 0), ("PST_EG", 1)] {
+            source += &format!("const {}: [[i32; Square::COUNT]; PieceKind::COUNT] = [\n", name);
+
+            for kind in PieceKind::all() {
+                source += &format!("    // {}\n    [\n", NAMES[kind]);
+
+                let rows: Vec<String> = (0..8).map(|rank| {
+                    let values: Vec<String> = (0..8).map(|file| format!("{:>4}", round(pst_index(kind, Square::from_raw(rank * 8 + file)), phase))).collect();
+
+                    format!("        {}", values.join(", "))
+                }).collect();
+
+                source += &rows.join(",\n");
+                source += if kind == PieceKind::King { "\n    ]\n" } else { "\n    ],\n" };
+            }
+
+            source += if phase == 0 { "];\n\n" } else { "];\n" };
+        }
+
+        source
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn labels() {
+        let startpos = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+        assert_eq!(parse(&format!("{} [1.0]", startpos)).map(|(_, result, score)| (result, score)), Some((Some(1.0), None)));
+        assert_eq!(parse(&format!("{} c9 \"1/2-1/2\";", startpos)).map(|(_, result, score)| (result, score)), Some((Some(0.5), None)));
+        assert_eq!(parse(&format!("{} | 0-1", startpos)).map(|(_, result, score)| (result, score)), Some((Some(0.0), None)));
+        assert_eq!(parse(&format!("{} | -35", startpos)).map(|(_, result, score)| (result, score)), Some((None, Some(-35))));
+        assert_eq!(parse(&format!("{} | 120 | 1.0", startpos)).map(|(_, result, score)| (result, score)), Some((Some(1.0), Some(120))));
+        assert_eq!(parse(&format!("{} [1]", startpos)).map(|(_, result, score)| (result, score)), Some((Some(1.0), None)));
+        assert_eq!(parse(&format!("{} c9 \"0\";", startpos)).map(|(_, result, score)| (result, score)), Some((Some(0.0), None)));
+        assert_eq!(parse(&format!("{} | 1", startpos)).map(|(_, result, score)| (result, score)), Some((None, Some(1))));
+        assert!(parse(startpos).is_none());
+        assert!(parse(&format!("{} [2.0]", startpos)).is_none());
+        assert!(parse(&format!("{} [35]", startpos)).is_none());
+    }
+
+    #[test]
+    fn scores() {
+        let mut tuner = Tuner::new(1);
+
+        tuner.add(&Board::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 0 1").unwrap(), None, None);
+        tuner.entries[0].score = Some(tuner.evaluate(&tuner.entries[0]).round() as i32);
+
+        // Scores say nothing about k, so fitting keeps it
+        assert_eq!(tuner.fit_k(), 1.0);
+
+        // A score equal to the evaluation is no error whatever k was fitted to
+        tuner.k = 3.0;
+
+        assert!(tuner.error() < 1e-6);
+    }
+
+    #[test]
+    fn tune() {
+        // Untouched weights print as the tables in the evaluation
+        let source = include_str!("eval.rs").replace('\r', "");
+
+        assert!(source.contains(&Tuner::new(1).source()));
+
+        let mut tuner = Tuner::new(2);
+        let lines = [
+            "4k3/8/8/8/8/8/8/R3K3 w - - 0 1 [1.0]",
+            "4k3/8/8/8/8/8/8/R3K3 b - - 0 1 [1.0]",
+            "r3k3/8/8/8/8/8/8/4K3 w - - 0 1 [0.0]",
+            "4k3/8/8/8/8/8/8/2B1K3 w - - 0 1 [0.5]",
+            "4k3/pppppppp/8/8/8/8/8/4K3 w - - 0 1 | -900"
+        ];
+
+        assert_eq!(tuner.load(lines.join("\n").as_bytes()), (5, 0));
+
+        tuner.fit_k();
+
+        let before = tuner.error();
+
+        tuner.run(Optimizer::Adam, 50, 1.0, |_, _| {});
+
+        assert!(tuner.error() < before);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,353 @@
+use std::{io::BufRead, sync::{atomic::Ordering, mpsc::{self, Receiver, RecvTimeoutError}}, time::{Duration, SystemTime, UNIX_EPOCH}};
+
+use crate::{chess::{board::Board, color::Color, moves::Move, random::Random}, engine::{book::Book, data::{Data, RootMove, Shared}, endgame::{self, Endgame}, params, search::Limits, threads, syzygy::Tablebase, tt::TT}};
+
+#[derive(Debug, Clone)]
+pub struct Options {
+    pub hash: usize,
+    pub threads: usize,
+    pub multipv: usize,
+    pub move_overhead: u64,
+    pub ponder: bool,
+    pub own_book: bool,
+    pub book_file: String,
+    pub syzygy_path: String
+}
+
+pub struct Uci {
+    board: Board,
+    options: Options,
+    book: Option<Book>,
+    tt: TT,
+    tablebase: Tablebase,
+    threads: Vec<Data>,
+    random: Random
+}
+
+impl Options {
+    pub const MAX_THREADS: usize = 1024;
+    pub const MAX_MULTIPV: usize = 256;
+    pub const DEFAULT_MOVE_OVERHEAD: u64 = 10;
+
+    pub fn new() -> Self {
+        Self {
+            hash: TT::DEFAULT_SIZE,
+            threads: 1,
+            multipv: 1,
+            move_overhead: Self::DEFAULT_MOVE_OVERHEAD,
+            ponder: false,
+            own_book: false,
+            book_file: String::new(),
+            syzygy_path: String::new()
+        }
+    }
+
+    pub fn print() {
+        println!("option name Hash type spin default {} min 1 max 65536", TT::DEFAULT_SIZE);
+        println!("option name Threads type spin default 1 min 1 max {}", Self::MAX_THREADS);
+        println!("option name MultiPV type spin default 1 min 1 max {}", Self::MAX_MULTIPV);
+        println!("option name Move Overhead type spin default {} min 0 max 5000", Self::DEFAULT_MOVE_OVERHEAD);
+        println!("option name Ponder type check default false");
+        println!("option name OwnBook type check default false");
+        println!("option name BookFile type string default <empty>");
+        println!("option name SyzygyPath type string default <empty>");
+
+        params::print();
+    }
+}
+
+impl Uci {
+    pub fn new() -> Self {
+        let seed = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |time| time.as_nanos() as u64);
+
+        Self {
+            board: Board::startpos().unwrap(),
+            options: Options::new(),
+            book: None,
+            tt: TT::new(TT::DEFAULT_SIZE),
+            tablebase: Tablebase::new(),
+            threads: vec![Data::new(0, Board::startpos().unwrap())],
+            random: Random::new(seed)
+        }
+    }
+
+    pub fn run(&mut self) {
+        // The built in endgame tables are solved in the background instead of in the middle of a search
+        std::thread::spawn(|| Endgame::all().for_each(|endgame| {
+            endgame::table(endgame);
+        }));
+
+        // Input is read on its own thread, so commands still arrive while searching
+        let (sender, receiver) = mpsc::channel();
+
+        std::thread::spawn(move || {
+            for line in std::io::stdin().lock().lines().map_while(Result::ok) {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        while let Ok(line) = receiver.recv() {
+            if !self.command(&line, &receiver) {
+                break;
+            }
+        }
+    }
+
+    // Returns false when told to quit
+    fn command(&mut self, line: &str, receiver: &Receiver<String>) -> bool {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+
+        match tokens.first().copied() {
+            Some("uci") => {
+                println!("id name tea");
+                println!("id author citrus610");
+                Options::print();
+                println!("uciok");
+            },
+            Some("isready") => println!("readyok"),
+            Some("ucinewgame") => {
+                self.board = Board::startpos().unwrap();
+                self.tt.clear();
+                self.resize_threads();
+            },
+            Some("setoption") => self.set_option(&tokens[1..]),
+            Some("position") => self.position(&tokens[1..]),
+            Some("go") => return self.go(&tokens[1..], receiver),
+            Some("d") => println!("{}", self.board),
+            Some("quit") => return false,
+            _ => {}
+        }
+
+        true
+    }
+
+    fn set_option(&mut self, tokens: &[&str]) {
+        let split = tokens.iter().position(|&token| token == "value").unwrap_or(tokens.len());
+        let name = tokens[..split].iter().skip_while(|&&token| token == "name").copied().collect::<Vec<_>>().join(" ");
+        let value = tokens[split..].iter().skip(1).copied().collect::<Vec<_>>().join(" ");
+
+        match name.to_lowercase().as_str() {
+            "hash" => match value.parse() {
+                Ok(hash) => {
+                    self.options.hash = hash;
+                    self.tt = TT::new(hash);
+                },
+                Err(_) => println!("info string invalid hash size {}", value)
+            },
+            "threads" => match value.parse::<usize>() {
+                Ok(threads) => {
+                    self.options.threads = threads.clamp(1, Options::MAX_THREADS);
+                    self.resize_threads();
+                },
+                Err(_) => println!("info string invalid thread count {}", value)
+            },
+            "multipv" => match value.parse::<usize>() {
+                Ok(multipv) => self.options.multipv = multipv.clamp(1, Options::MAX_MULTIPV),
+                Err(_) => println!("info string invalid multipv {}", value)
+            },
+            "move overhead" => match value.parse() {
+                Ok(overhead) => self.options.move_overhead = overhead,
+                Err(_) => println!("info string invalid move overhead {}", value)
+            },
+            "ponder" => self.options.ponder = value == "true",
+            "ownbook" => self.options.own_book = value == "true",
+            "bookfile" => {
+                self.options.book_file = value;
+                self.book = None;
+
+                if !self.options.book_file.is_empty() && self.options.book_file != "<empty>" {
+                    match Book::load(&self.options.book_file) {
+                        Ok(book) => self.book = Some(book),
+                        Err(error) => println!("info string can't read book {}: {}", self.options.book_file, error)
+                    }
+                }
+            },
+            "syzygypath" => {
+                self.options.syzygy_path = value;
+                self.tablebase = Tablebase::new();
+
+                if !self.options.syzygy_path.is_empty() && self.options.syzygy_path != "<empty>" {
+                    let count = self.tablebase.add_path(&self.options.syzygy_path);
+
+                    println!("info string found {} tablebase files up to {} pieces", count, self.tablebase.max_pieces());
+                }
+            },
+            _ if value.parse().is_ok_and(|value| params::set(&name, value)) => {},
+            _ => println!("info string unknown option {}", name)
+        }
+    }
+
+    // Fresh threads with empty history tables
+    fn resize_threads(&mut self) {
+        self.threads = (0..self.options.threads).map(|id| Data::new(id as u32, self.board.clone())).collect();
+    }
+
+    fn position(&mut self, tokens: &[&str]) {
+        let moves = tokens.iter().position(|&token| token == "moves").unwrap_or(tokens.len());
+
+        let board = match tokens.first().copied() {
+            Some("startpos") => Board::startpos(),
+            Some("fen") => Board::from_fen(&tokens[1..moves].join(" ")),
+            _ => return
+        };
+
+        let Ok(mut board) = board else {
+            println!("info string invalid position");
+            return;
+        };
+
+        for token in tokens.iter().skip(moves + 1) {
+            match Self::parse_move(&board, token) {
+                Some(mv) => board.make(mv),
+                None => {
+                    println!("info string illegal move {}", token);
+                    break;
+                }
+            }
+        }
+
+        self.board = board;
+    }
+
+    fn parse_move(board: &Board, token: &str) -> Option<Move> {
+        board
+            .generate_moves()
+            .iter_moves()
+            .copied()
+            .find(|&mv| mv.to_string() == token && board.is_legal(mv))
+    }
+
+    // Searches on another thread while listening for stop, returns false when told to quit
+    fn go(&mut self, tokens: &[&str], receiver: &Receiver<String>) -> bool {
+        let limits = self.limits(tokens);
+        let ponder = tokens.contains(&"ponder");
+
+        // A book move can't be sent before ponderhit, so pondering always searches
+        let book_move = match (&self.book, self.options.own_book && !ponder) {
+            (Some(book), true) => book.pick(&self.board, &mut self.random),
+            _ => None
+        };
+
+        if let Some(mv) = book_move {
+            println!("bestmove {}", mv);
+            return true;
+        }
+
+        let shared = Shared::new(self.threads.len());
+        let stop = &shared.stop;
+
+        shared.ponder.store(ponder, Ordering::Relaxed);
+        let mut quit = false;
+        // Commands sent after bestmove can arrive before the search thread is done, they run once it is
+        let mut pending = Vec::new();
+
+        for data in self.threads.iter_mut() {
+            data.reset(self.board.clone());
+        }
+
+        // Boards cache attack maps in cells, so the search thread finds the ponder move on its own copy
+        let board = self.board.clone();
+        let (workers, tt, tablebase, shared) = (&mut self.threads, &self.tt, &self.tablebase, &shared);
+
+        std::thread::scope(|scope| {
+            let search = scope.spawn(move || {
+                let best = threads::search(workers, tt, tablebase, shared, limits);
+
+                match best.map(|root| (root.mv, Self::ponder_move(&board, tt, &root))) {
+                    Some((mv, Some(ponder))) => println!("bestmove {} ponder {}", mv, ponder),
+                    Some((mv, None)) => println!("bestmove {}", mv),
+                    None => println!("bestmove 0000")
+                }
+            });
+
+            while !search.is_finished() {
+                match receiver.recv_timeout(Duration::from_millis(1)).as_deref().map(str::trim) {
+                    Ok("stop") => stop.store(true, Ordering::Relaxed),
+                    Ok("ponderhit") => shared.ponder.store(false, Ordering::Relaxed),
+                    Ok("quit") => {
+                        stop.store(true, Ordering::Relaxed);
+                        quit = true;
+                    },
+                    Ok("isready") => println!("readyok"),
+                    Ok(line) => pending.push(line.to_string()),
+                    Err(RecvTimeoutError::Timeout) => {},
+                    Err(RecvTimeoutError::Disconnected) => {
+                        // Nothing can stop an infinite or pondering search anymore
+                        if limits.infinite || shared.is_pondering() {
+                            stop.store(true, Ordering::Relaxed);
+                        }
+
+                        break;
+                    }
+                }
+            }
+        });
+
+        !quit && pending.iter().all(|line| self.command(line, receiver))
+    }
+
+    // The reply expected to the best move, from the line or else from the transposition table
+    fn ponder_move(board: &Board, tt: &TT, root: &RootMove) -> Option<Move> {
+        if root.pv.len() >= 2 {
+            return Some(root.pv[1]);
+        }
+
+        let mut board = board.clone();
+
+        board.make(root.mv);
+
+        let mv = tt.probe(board.state.key())?.mv;
+
+        (mv.is_some() && board.is_pseudo_legal(mv) && board.is_legal(mv)).then_some(mv)
+    }
+
+    fn limits(&self, tokens: &[&str]) -> Limits {
+        let mut limits = Limits { overhead: self.options.move_overhead, multipv: self.options.multipv, ..Limits::new() };
+
+        for pair in tokens.windows(2) {
+            // Some guis send negative times once the clock runs out
+            let value = pair[1].parse::<i64>().ok().map(|value| value.max(0) as u64);
+
+            match pair[0] {
+                "depth" => limits.depth = value.map_or(limits.depth, |depth| depth.min(Limits::MAX_DEPTH as u64) as i32),
+                "nodes" => limits.nodes = value,
+                "movetime" => limits.movetime = value,
+                "mate" => limits.mate = value.map(|mate| mate.min(Limits::MAX_DEPTH as u64) as i32),
+                "wtime" => limits.time[Color::White] = value,
+                "btime" => limits.time[Color::Black] = value,
+                "winc" => limits.inc[Color::White] = value.unwrap_or(0),
+                "binc" => limits.inc[Color::Black] = value.unwrap_or(0),
+                "movestogo" => limits.movestogo = value,
+                _ => {}
+            }
+        }
+
+        limits.infinite = tokens.contains(&"infinite");
+
+        limits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pending() {
+        let mut uci = Uci::new();
+        let (sender, receiver) = mpsc::channel();
+
+        // Read while the infinite search runs, the position must still be set once it stops
+        sender.send("position startpos moves e2e4".to_string()).unwrap();
+        sender.send("stop".to_string()).unwrap();
+
+        assert!(uci.command("go infinite", &receiver));
+        assert_eq!(uci.board.to_fen(), "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1");
+
+        sender.send("quit".to_string()).unwrap();
+
+        assert!(!uci.command("go infinite", &receiver));
+    }
+}
\ No newline at end of file
@@ -1,20 +1,230 @@
 #![allow(dead_code)]
-use std::time::Instant;
+use std::{env, fs, io::{BufRead, BufReader, BufWriter, Write}, thread, time::Instant};
 
-use crate::chess::{board::Board, movegen::perft};
+use crate::{chess::{board::Board, fuzz::fuzz, packed::{PackedBoard, PackedReader, PackedWriter}, perft::{Perft, PerftCase}, pgn::PgnReader}, engine::{book::BookBuilder, datagen::Datagen, syzygy::Tablebase, tuner::{Optimizer, Tuner}, uci::Uci}};
 
 mod chess;
 mod engine;
 
-fn main() {
-    let mut board = Board::startpos().unwrap();
+fn perftsuite(path: &str, depth: i32) {
+    let epd = fs::read_to_string(path).expect("can't read epd file!");
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+    let perft = Perft::new(64, threads);
+
+    let mut passed = 0;
+    let mut failed = 0;
+
+    for line in epd.lines().filter(|line| !line.trim().is_empty()) {
+        let case = match PerftCase::from_epd(line) {
+            Ok(case) => case,
+            Err(error) => {
+                println!("invalid epd: {} ({:?})", line, error);
+                failed += 1;
+                continue;
+            }
+        };
+
+        match perft.check(&case, depth) {
+            Ok(()) => {
+                println!("ok: {}", case.fen);
+                passed += 1;
+            },
+            Err(mismatch) => {
+                println!("mismatch: {}", mismatch);
+                failed += 1;
+            }
+        }
+    }
+
+    println!("passed: {}", passed);
+    println!("failed: {}", failed);
+}
+
+fn book(pgn: &str, output: &str, mut builder: BookBuilder) {
+    let file = fs::File::open(pgn).expect("can't read pgn file!");
+
+    let mut added = 0;
+    let mut skipped = 0;
+
+    for game in PgnReader::new(BufReader::new(file)) {
+        match builder.add_game(&game) {
+            true => added += 1,
+            false => skipped += 1
+        }
+    }
+
+    let entries = builder.write(output).expect("can't write book file!");
+
+    println!("games: {}", added);
+    println!("skipped: {}", skipped);
+    println!("entries: {}", entries);
+}
+
+fn tune(path: &str, epochs: usize, optimizer: Optimizer, rate: f64) {
+    let file = fs::File::open(path).expect("can't read data file!");
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+    let mut tuner = Tuner::new(threads);
+
+    let (added, skipped) = tuner.load(BufReader::new(file));
+
+    println!("positions: {}", added);
+    println!("skipped: {}", skipped);
+    println!("k: {:.4}", tuner.fit_k());
+    println!("error: {:.6}", tuner.error());
+
+    tuner.run(optimizer, epochs, rate, |epoch, error| {
+        if epoch % 100 == 0 {
+            println!("epoch {}: error {:.6}", epoch, error);
+        }
+    });
+
+    println!();
+    print!("{}", tuner.source());
+}
+
+// Packs "fen | score | result" lines into 32 byte records, or unpacks them back when the input ends in .bin
+fn convert(input: &str, output: &str) {
+    let reader = BufReader::new(fs::File::open(input).expect("can't read input file!"));
+    let mut writer = BufWriter::new(fs::File::create(output).expect("can't write output file!"));
+
+    let mut converted = 0;
+    let mut skipped = 0;
+
+    if input.ends_with(".bin") {
+        let mut reader = PackedReader::new(reader);
+
+        for packed in reader.by_ref() {
+            writeln!(writer, "{}", packed.to_line()).expect("can't write output file!");
+            converted += 1;
+        }
+
+        skipped = reader.skipped();
+    }
+    else {
+        let mut packed_writer = PackedWriter::new(&mut writer);
+
+        for line in reader.lines().map_while(Result::ok).filter(|line| !line.trim().is_empty()) {
+            match PackedBoard::from_line(&line) {
+                Some(packed) => {
+                    packed_writer.write(&packed).expect("can't write output file!");
+                    converted += 1;
+                },
+                None => skipped += 1
+            }
+        }
+    }
+
+    writer.flush().expect("can't write output file!");
+
+    println!("positions: {}", converted);
+    println!("skipped: {}", skipped);
+}
+
+fn bench() {
+    let board = Board::startpos().unwrap();
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+    let perft = Perft::new(64, threads);
 
     let time = Instant::now();
-    let count = perft::<true>(&mut board, 7);
+    let count = perft.run::<true>(&board, 7);
     let elasped = time.elapsed().as_millis();
     let knps = count as u128 / elasped;
 
     println!("perft: {}", count);
     println!("time: {} ms", elasped);
     println!("nps: {} kn/s", knps);
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+
+    match args.get(1).map(String::as_str) {
+        Some("perftsuite") => {
+            let path = args.get(2).map_or("res/perftsuite.epd", String::as_str);
+            let depth = args.get(3).and_then(|depth| depth.parse().ok()).unwrap_or(6);
+
+            perftsuite(path, depth);
+            return;
+        },
+        Some("book") => {
+            let (Some(pgn), Some(output)) = (args.get(2), args.get(3)) else {
+                println!("usage: tea book <pgn> <output> [ply] [min games] [min score]");
+                return;
+            };
+
+            let ply = args.get(4).and_then(|ply| ply.parse().ok()).unwrap_or(20);
+            let min_games = args.get(5).and_then(|games| games.parse().ok()).unwrap_or(1);
+            let min_score = args.get(6).and_then(|score| score.parse().ok()).unwrap_or(0);
+
+            book(pgn, output, BookBuilder::new(ply, min_games, min_score));
+            return;
+        },
+        Some("tune") => {
+            let Some(path) = args.get(2) else {
+                println!("usage: tea tune <data> [epochs] [adam|gd] [rate]");
+                return;
+            };
+
+            let epochs = args.get(3).and_then(|epochs| epochs.parse().ok()).unwrap_or(1000);
+            let optimizer = match args.get(4).map(String::as_str) {
+                Some("gd") => Optimizer::Gradient,
+                _ => Optimizer::Adam
+            };
+            let rate = args.get(5).and_then(|rate| rate.parse().ok()).unwrap_or(optimizer.default_rate());
+
+            tune(path, epochs, optimizer, rate);
+            return;
+        },
+        Some("datagen") => {
+            let Some(output) = args.get(2) else {
+                println!("usage: tea datagen <output> [games] [threads] [nodes] [syzygy path]");
+                return;
+            };
+
+            let games = args.get(3).and_then(|games| games.parse().ok()).unwrap_or(1000);
+            let threads = args.get(4).and_then(|threads| threads.parse().ok()).unwrap_or(1);
+            let nodes = args.get(5).and_then(|nodes| nodes.parse().ok()).unwrap_or(5000);
+            let mut tablebase = Tablebase::new();
+
+            if let Some(path) = args.get(6) {
+                println!("tablebase files: {}", tablebase.add_path(path));
+            }
+
+            let seed = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map_or(0, |time| time.as_nanos() as u64);
+
+            match Datagen::new(nodes, 8, tablebase).run(output, games, threads, seed) {
+                Ok(positions) => println!("positions: {}", positions),
+                Err(error) => println!("can't write {}: {}", output, error)
+            }
+
+            return;
+        },
+        Some("convert") => {
+            let (Some(input), Some(output)) = (args.get(2), args.get(3)) else {
+                println!("usage: tea convert <input> <output>");
+                return;
+            };
+
+            convert(input, output);
+            return;
+        },
+        Some("perft") => {
+            bench();
+            return;
+        },
+        Some("fuzz") => {
+            let games = args.get(2).and_then(|games| games.parse().ok()).unwrap_or(1000);
+            let seed = args.get(3).and_then(|seed| seed.parse().ok()).unwrap_or(0x7ea);
+
+            match fuzz(seed, games, 400) {
+                Ok(positions) => println!("positions: {}", positions),
+                Err(discrepancy) => println!("discrepancy: {}", discrepancy)
+            }
+
+            return;
+        },
+        _ => {}
+    }
+
+    Uci::new().run();
 }
\ No newline at end of file